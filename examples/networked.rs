@@ -0,0 +1,178 @@
+use std::str::FromStr;
+use std::sync::mpsc::{Receiver, RecvTimeoutError, Sender, channel};
+use std::thread;
+use std::time::Duration;
+
+use zk_sudoku_prover::*;
+
+/// The puzzle both sides agree on before the session starts
+const PUZZLE: PuzzleId = PuzzleId(0);
+
+/// How long either side waits for the other's next message before giving up on it
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+fn main() {
+    let line = "296541378851273694743698251915764832387152946624839517139486725478325169562917483";
+    let board = SudokuGrid::from_str(line).unwrap();
+
+    // Publish every third cell as a clue
+    let puzzle = board.keep_every(3);
+    let input = ProofInput::new(&puzzle, &board).unwrap();
+
+    let (passed, confidence) = exchange(&input, 20, true).unwrap();
+    println!("Passed: {passed}, confidence: {confidence:.2}%");
+}
+
+/// Runs `rounds` rounds with the prover and verifier on their own threads, talking only
+/// through channels as they would over a socket. Returns whether every round passed and
+/// the verifier's confidence, or [`ZkProofError::Timeout`] if either side goes silent.
+fn exchange(input: &ProofInput, rounds: usize, verbose: bool) -> Result<(bool, f64), ZkProofError> {
+    let (to_verifier, verifier_inbox) = channel();
+    let (to_prover, prover_inbox) = channel();
+
+    // The verifier only ever sees the public puzzle
+    let mut verifier = MultiVerifier::new();
+    verifier.insert(
+        PUZZLE,
+        Verifier::new(Graph::from_puzzle(input.puzzle()).edge_map()),
+    );
+    let (prover, _) = Prover::new(input.solution())?;
+    let mut multi_prover = MultiProver::new();
+    multi_prover.insert(PUZZLE, prover);
+
+    let verifier_thread = thread::spawn(move || {
+        run_verifier(verifier, &verifier_inbox, &to_prover, verbose, TIMEOUT)
+    });
+    let passed = run_prover(
+        multi_prover,
+        rounds,
+        &to_verifier,
+        &prover_inbox,
+        verbose,
+        TIMEOUT,
+    );
+    // Hanging up ends the verifier's loop
+    drop(to_verifier);
+
+    let verifier = verifier_thread.join().unwrap()?;
+    let confidence = verifier
+        .confidence(PUZZLE)
+        .ok_or(ZkProofError::UnknownPuzzle(PUZZLE.0))?;
+    Ok((passed?, confidence))
+}
+
+fn run_prover(
+    mut prover: MultiProver,
+    rounds: usize,
+    outbox: &Sender<ProtocolMessage>,
+    inbox: &Receiver<ProtocolMessage>,
+    verbose: bool,
+    timeout: Duration,
+) -> Result<bool, ZkProofError> {
+    for _ in 0..rounds {
+        send(outbox, prover.start_round(PUZZLE)?, "prover", verbose);
+        let Some(challenge) = receive(inbox, timeout)? else {
+            return Ok(false);
+        };
+        send(outbox, prover.handle(challenge)?, "prover", verbose);
+
+        match receive(inbox, timeout)? {
+            Some(ProtocolMessage::Result { result, .. }) if result.success => {}
+            _ => return Ok(false),
+        }
+    }
+    Ok(true)
+}
+
+/// Answers every message until the prover hangs up, then hands the verifier back
+fn run_verifier(
+    mut verifier: MultiVerifier,
+    inbox: &Receiver<ProtocolMessage>,
+    outbox: &Sender<ProtocolMessage>,
+    verbose: bool,
+    timeout: Duration,
+) -> Result<MultiVerifier, ZkProofError> {
+    while let Some(message) = receive(inbox, timeout)? {
+        send(outbox, verifier.handle(message)?, "verifier", verbose);
+    }
+    Ok(verifier)
+}
+
+/// Waits up to `timeout` for the peer's next message, `None` once it has hung up
+fn receive(
+    inbox: &Receiver<ProtocolMessage>,
+    timeout: Duration,
+) -> Result<Option<ProtocolMessage>, ZkProofError> {
+    match inbox.recv_timeout(timeout) {
+        Ok(message) => Ok(Some(message)),
+        Err(RecvTimeoutError::Disconnected) => Ok(None),
+        Err(RecvTimeoutError::Timeout) => Err(ZkProofError::Timeout),
+    }
+}
+
+fn send(outbox: &Sender<ProtocolMessage>, message: ProtocolMessage, from: &str, verbose: bool) {
+    if verbose {
+        println!("{from} -> {}", message_type(&message));
+    }
+    outbox.send(message).unwrap();
+}
+
+fn message_type(message: &ProtocolMessage) -> &'static str {
+    match message {
+        ProtocolMessage::Commitment { .. } => "commitment",
+        ProtocolMessage::Challenge { .. } => "challenge",
+        ProtocolMessage::Response { .. } => "response",
+        ProtocolMessage::Result { .. } => "result",
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_exchange() {
+        let solution = SudokuGrid::from_str(
+            "296541378851273694743698251915764832387152946624839517139486725478325169562917483",
+        )
+        .unwrap();
+        let input = ProofInput::from_solution(&solution).unwrap();
+
+        let (passed, confidence) = exchange(&input, 5, false).unwrap();
+        assert!(passed);
+        assert!(confidence > 0.0);
+    }
+
+    #[test]
+    fn test_silent_peer_times_out() {
+        let solution = SudokuGrid::from_str(
+            "296541378851273694743698251915764832387152946624839517139486725478325169562917483",
+        )
+        .unwrap();
+        let timeout = Duration::from_millis(50);
+
+        // A verifier that takes the commitment but never answers, without hanging up
+        let (prover, _) = Prover::new(&solution).unwrap();
+        let mut multi_prover = MultiProver::new();
+        multi_prover.insert(PUZZLE, prover);
+        let (to_verifier, _verifier_inbox) = channel();
+        let (_to_prover, prover_inbox) = channel();
+        assert!(matches!(
+            run_prover(multi_prover, 1, &to_verifier, &prover_inbox, false, timeout),
+            Err(ZkProofError::Timeout)
+        ));
+
+        // A prover that stays connected but never sends anything
+        let mut verifier = MultiVerifier::new();
+        verifier.insert(
+            PUZZLE,
+            Verifier::new(Graph::from_sudoku(&solution).edge_map()),
+        );
+        let (_to_verifier, verifier_inbox) = channel();
+        let (to_prover, _prover_inbox) = channel();
+        assert!(matches!(
+            run_verifier(verifier, &verifier_inbox, &to_prover, false, timeout),
+            Err(ZkProofError::Timeout)
+        ));
+    }
+}
@@ -20,7 +20,10 @@ fn main() {
     println!("Board:\n{}", board);
     println!("Valid: {}", board.is_valid_solution());
 
-    let mut zk_protocol = ZKProtocol::new(&board).unwrap();
+    // Publish every third cell as a clue
+    let puzzle = board.keep_every(3);
+
+    let mut zk_protocol = ZKProtocol::new(&ProofInput::new(&puzzle, &board).unwrap()).unwrap();
 
     let t1 = std::time::Instant::now();
 
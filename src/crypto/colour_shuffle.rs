@@ -1,6 +1,6 @@
 use crate::Value;
-use rand::rng;
 use rand::seq::SliceRandom;
+use rand::{Rng, rng};
 
 /// A permutation of colours (values 1-9)
 #[derive(Debug, Clone)]
@@ -12,9 +12,13 @@ pub struct ColourShuffle {
 impl ColourShuffle {
     /// Create a new random colour shuffle
     pub fn new_random() -> Self {
-        let mut rng = rng();
+        Self::new_with_rng(&mut rng())
+    }
+
+    /// Create a new colour shuffle drawn from the given RNG
+    pub fn new_with_rng<R: Rng + ?Sized>(rng: &mut R) -> Self {
         let mut values = Value::ALL_VALUES;
-        values.shuffle(&mut rng);
+        values.shuffle(rng);
 
         Self { value_map: values }
     }
@@ -28,6 +32,21 @@ impl ColourShuffle {
         let index = self.value_map.iter().position(|&v| v == value).unwrap();
         Value::from_index(index)
     }
+
+    /// The rank of this permutation in lexicographic order, in `0..9!`.
+    /// Two shuffles are the same permutation exactly when their indices match.
+    pub fn as_permutation_index(&self) -> u32 {
+        let mut index = 0;
+        for (i, value) in self.value_map.iter().enumerate() {
+            // Lehmer code: how many later entries are smaller than this one
+            let smaller = self.value_map[i + 1..]
+                .iter()
+                .filter(|later| *later < value)
+                .count() as u32;
+            index = index * (9 - i as u32) + smaller;
+        }
+        index
+    }
 }
 
 #[cfg(test)]
@@ -43,4 +62,19 @@ mod tests {
 
         assert_eq!(original, reversed);
     }
+
+    #[test]
+    fn test_permutation_index() {
+        let identity = ColourShuffle {
+            value_map: Value::ALL_VALUES,
+        };
+        assert_eq!(identity.as_permutation_index(), 0);
+
+        let mut reversed = Value::ALL_VALUES;
+        reversed.reverse();
+        let reversed = ColourShuffle {
+            value_map: reversed,
+        };
+        assert_eq!(reversed.as_permutation_index(), 362_879);
+    }
 }
@@ -1,9 +1,16 @@
 use bytes::Bytes;
+use petgraph::graph::NodeIndex;
 use rand::TryRngCore;
 use std::marker::PhantomData;
 use thiserror::Error;
 
-use crate::Value;
+use crate::{CommitmentHash, Value};
+
+/// Shortest reveal nonce accepted by default, in bytes. Shorter nonces weaken hiding.
+pub const MIN_NONCE_LEN: usize = 16;
+
+/// Nonce length used by [`Commitment::new`], in bytes
+pub const DEFAULT_NONCE_LEN: usize = 32;
 
 #[derive(Debug, Clone, Copy)]
 pub struct Hidden;
@@ -29,13 +36,41 @@ impl CommitmentKey {
     pub(crate) fn new(value: Value, nonce: Bytes) -> Self {
         Self { value, nonce }
     }
+
+    /// Whether this key opens `hash` for `node` under `hasher`, without needing the
+    /// commitment itself. Handy for checking logged hashes and keys after the fact.
+    pub fn matches_hash(
+        &self,
+        hash: &[u8],
+        node: NodeIndex,
+        hasher: &dyn CommitmentHasher,
+    ) -> bool {
+        hasher.hash(self.value, node, &self.nonce) == hash
+    }
+}
+
+/// The hash a commitment is made with, over the committed value, the node it's bound to
+/// and its nonce
+pub trait CommitmentHasher {
+    fn hash(&self, value: Value, node: NodeIndex, nonce: &[u8]) -> Bytes;
+}
+
+/// Blake3 over the value byte, the node index as a little-endian `u64` and then the nonce,
+/// as used by [`Commitment::new`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Blake3Hasher;
+
+impl CommitmentHasher for Blake3Hasher {
+    fn hash(&self, value: Value, node: NodeIndex, nonce: &[u8]) -> Bytes {
+        compute_hash(value, node.index(), nonce)
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct Commitment<S = Hidden> {
     // Common fields
-    hash: Bytes,    // The committed hash
-    node_id: usize, // The node this commitment is for
+    hash: CommitmentHash, // The committed hash
+    node_id: usize,       // The node this commitment is for
     // State-specific fields
     key: Option<CommitmentKey>,
     _marker: PhantomData<S>,
@@ -43,14 +78,23 @@ pub struct Commitment<S = Hidden> {
 
 impl Commitment<Hidden> {
     /// Create a new commitment for a value
-    pub fn new(value: Value, node_id: usize) -> (Self, CommitmentKey) {
-        let nonce = generate_nonce(32); // 32 bytes of randomness
-        let hash = compute_hash(value, &nonce);
+    pub fn new(value: Value, node: NodeIndex) -> (Self, CommitmentKey) {
+        Self::new_with_nonce_len(value, node, DEFAULT_NONCE_LEN)
+    }
+
+    /// Like [`Commitment::new`], with `nonce_len` bytes of randomness in the nonce
+    pub fn new_with_nonce_len(
+        value: Value,
+        node: NodeIndex,
+        nonce_len: usize,
+    ) -> (Self, CommitmentKey) {
+        let nonce = generate_nonce(nonce_len);
+        let hash = CommitmentHash::new(compute_hash(value, node.index(), &nonce));
 
         (
             Self {
                 hash,
-                node_id,
+                node_id: node.index(),
                 key: None,
                 _marker: PhantomData,
             },
@@ -61,14 +105,45 @@ impl Commitment<Hidden> {
     /// Reveal the commitment with a key
     /// Can only get a Commitment<Revealed> if the key is correct
     pub fn reveal(self, key: CommitmentKey) -> Result<Commitment<Revealed>, CommitmentError> {
-        match self.verify_hash(&key) {
+        self.verify(&key)?;
+        Ok(Commitment {
+            hash: self.hash,
+            node_id: self.node_id,
+            key: Some(key),
+            _marker: PhantomData,
+        })
+    }
+
+    pub(crate) fn from_parts(hash: Bytes, node_id: usize) -> Self {
+        Self {
+            hash: CommitmentHash::new(hash),
+            node_id,
+            key: None,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Check that a key opens this commitment without consuming it.
+    /// Useful when the caller only needs the verdict and already owns the key.
+    pub fn verify(&self, key: &CommitmentKey) -> Result<(), CommitmentError> {
+        self.verify_with_min_nonce(key, MIN_NONCE_LEN)
+    }
+
+    /// Like [`Commitment::verify`], but with a caller-chosen minimum nonce length
+    pub fn verify_with_min_nonce(
+        &self,
+        key: &CommitmentKey,
+        min_nonce_len: usize,
+    ) -> Result<(), CommitmentError> {
+        if key.nonce.len() < min_nonce_len {
+            return Err(CommitmentError::NonceTooShort {
+                len: key.nonce.len(),
+                min: min_nonce_len,
+            });
+        }
+        match self.verify_hash(key) {
             false => Err(CommitmentError::InvalidReveal),
-            true => Ok(Commitment {
-                hash: self.hash,
-                node_id: self.node_id,
-                key: Some(key),
-                _marker: PhantomData,
-            }),
+            true => Ok(()),
         }
     }
 }
@@ -88,13 +163,18 @@ impl<S> Commitment<S> {
         self.node_id
     }
 
-    pub fn hash(&self) -> &[u8] {
+    /// The graph node this commitment is for
+    pub fn node_index(&self) -> NodeIndex {
+        NodeIndex::new(self.node_id)
+    }
+
+    pub fn hash(&self) -> &CommitmentHash {
         &self.hash
     }
 
     // Helper for validation
     fn verify_hash(&self, key: &CommitmentKey) -> bool {
-        compute_hash(key.value, &key.nonce) == self.hash
+        compute_hash(key.value, self.node_id, &key.nonce) == self.hash.as_bytes()
     }
 }
 
@@ -105,10 +185,12 @@ fn generate_nonce(length: usize) -> Bytes {
     Bytes::from_owner(nonce)
 }
 
-/// Compute a hash for a value and nonce
-fn compute_hash(value: Value, nonce: &[u8]) -> Bytes {
+/// Compute a hash for a value and nonce, bound to the node it's committed for so that a
+/// commitment can't be moved to another node
+fn compute_hash(value: Value, node_id: usize, nonce: &[u8]) -> Bytes {
     let mut hasher = blake3::Hasher::new();
     hasher.update(&[value.to_numeric()]);
+    hasher.update(&(node_id as u64).to_le_bytes());
     hasher.update(nonce);
     Bytes::copy_from_slice(hasher.finalize().as_bytes())
 }
@@ -117,6 +199,8 @@ fn compute_hash(value: Value, nonce: &[u8]) -> Bytes {
 pub enum CommitmentError {
     #[error("Invalid reveal - hash does not match")]
     InvalidReveal,
+    #[error("Nonce too short: {len} bytes, expected at least {min}")]
+    NonceTooShort { len: usize, min: usize },
 }
 
 #[cfg(test)]
@@ -125,7 +209,7 @@ mod tests {
 
     #[test]
     fn test_commitment() {
-        let (commitment, key) = Commitment::new(Value::Five, 1);
+        let (commitment, key) = Commitment::new(Value::Five, NodeIndex::new(1));
         let revealed = commitment.reveal(key.clone()).unwrap();
         let revealed_key = revealed.key().clone();
         assert_eq!(revealed_key.value, Value::Five);
@@ -134,7 +218,7 @@ mod tests {
 
     #[test]
     fn test_invalid_reveal() {
-        let (commitment, _) = Commitment::new(Value::Five, 1);
+        let (commitment, _) = Commitment::new(Value::Five, NodeIndex::new(1));
         let invalid_key = CommitmentKey {
             value: Value::Six,
             nonce: Bytes::from(vec![0; 32]),
@@ -142,17 +226,86 @@ mod tests {
         assert!(commitment.reveal(invalid_key).is_err());
     }
 
+    #[test]
+    fn test_verify_borrowed() {
+        let (commitment, key) = Commitment::new(Value::Two, NodeIndex::new(7));
+        assert!(commitment.verify(&key).is_ok());
+
+        let wrong_key = CommitmentKey {
+            value: Value::Three,
+            nonce: key.nonce.clone(),
+        };
+        assert!(matches!(
+            commitment.verify(&wrong_key),
+            Err(CommitmentError::InvalidReveal)
+        ));
+
+        // The commitment is still usable after being verified
+        assert!(commitment.reveal(key).is_ok());
+    }
+
+    #[test]
+    fn test_key_matches_hash() {
+        let (commitment, key) = Commitment::new(Value::Six, NodeIndex::new(3));
+        let node = NodeIndex::new(3);
+        assert!(key.matches_hash(commitment.hash().as_bytes(), node, &Blake3Hasher));
+
+        let mut nonce = key.nonce().to_vec();
+        nonce[0] ^= 1;
+        let wrong_nonce = CommitmentKey::new(key.value(), nonce.into());
+        assert!(!wrong_nonce.matches_hash(commitment.hash().as_bytes(), node, &Blake3Hasher));
+
+        let wrong_value = CommitmentKey::new(Value::Seven, key.nonce.clone());
+        assert!(!wrong_value.matches_hash(commitment.hash().as_bytes(), node, &Blake3Hasher));
+    }
+
+    #[test]
+    fn test_commitment_bound_to_node() {
+        let (commitment, key) = Commitment::new(Value::Two, NodeIndex::new(5));
+        assert!(commitment.verify(&key).is_ok());
+
+        // The same hash and key presented as node 6's commitment don't open
+        let moved = Commitment::from_parts(Bytes::copy_from_slice(commitment.hash().as_bytes()), 6);
+        assert!(matches!(
+            moved.verify(&key),
+            Err(CommitmentError::InvalidReveal)
+        ));
+        assert!(moved.reveal(key.clone()).is_err());
+        assert!(!key.matches_hash(
+            commitment.hash().as_bytes(),
+            NodeIndex::new(6),
+            &Blake3Hasher
+        ));
+    }
+
+    #[test]
+    fn test_short_nonce_rejected() {
+        let value = Value::Four;
+        let nonce: Bytes = vec![3; 4].into();
+        let commitment = Commitment::from_parts(compute_hash(value, 0, &nonce), 0);
+        let key = CommitmentKey { value, nonce };
+
+        // The hash matches, but the nonce is too short to hide the value
+        assert!(commitment.verify_hash(&key));
+        assert!(matches!(
+            commitment.verify(&key),
+            Err(CommitmentError::NonceTooShort { len: 4, min: 16 })
+        ));
+        assert!(commitment.verify_with_min_nonce(&key, 4).is_ok());
+        assert!(commitment.reveal(key).is_err());
+    }
+
     #[test]
     fn test_commitment_creation_and_revelation() {
         let node_id = 42;
         let value = Value::Three;
 
         // Create a commitment
-        let (commitment, key) = Commitment::new(value, node_id);
+        let (commitment, key) = Commitment::new(value, NodeIndex::new(node_id));
 
         // Verify the commitment properties
         assert_eq!(commitment.node_id(), node_id);
-        assert!(!commitment.hash().is_empty());
+        assert!(!commitment.hash().as_bytes().is_empty());
 
         // Reveal the commitment
         let revealed = commitment.clone().reveal(key.clone()).unwrap();
@@ -164,9 +317,20 @@ mod tests {
         assert_eq!(revealed.key().nonce(), key.nonce());
     }
 
+    #[test]
+    fn test_node_index_round_trip() {
+        let node = NodeIndex::new(83);
+        let (commitment, key) = Commitment::new(Value::Eight, node);
+        assert_eq!(commitment.node_index(), node);
+        assert_eq!(commitment.node_id(), node.index());
+
+        let revealed = commitment.reveal(key).unwrap();
+        assert_eq!(revealed.node_index(), node);
+    }
+
     #[test]
     fn test_invalid_reveals() {
-        let (commitment, _) = Commitment::new(Value::Five, 1);
+        let (commitment, _) = Commitment::new(Value::Five, NodeIndex::new(1));
 
         // Test with wrong value
         let invalid_value_key = CommitmentKey {
@@ -186,9 +350,9 @@ mod tests {
     #[test]
     fn test_multiple_commitments() {
         // Create multiple commitments
-        let (commitment1, key1) = Commitment::new(Value::One, 1);
-        let (commitment2, key2) = Commitment::new(Value::Two, 2);
-        let (commitment3, key3) = Commitment::new(Value::Three, 3);
+        let (commitment1, key1) = Commitment::new(Value::One, NodeIndex::new(1));
+        let (commitment2, key2) = Commitment::new(Value::Two, NodeIndex::new(2));
+        let (commitment3, key3) = Commitment::new(Value::Three, NodeIndex::new(3));
 
         // Reveal in different order
         let revealed2 = commitment2.reveal(key2).unwrap();
@@ -209,8 +373,8 @@ mod tests {
     #[test]
     fn test_same_value_different_commitments() {
         // Two commitments with the same value should have different hashes
-        let (commitment1, _) = Commitment::new(Value::Seven, 5);
-        let (commitment2, _) = Commitment::new(Value::Seven, 5);
+        let (commitment1, _) = Commitment::new(Value::Seven, NodeIndex::new(5));
+        let (commitment2, _) = Commitment::new(Value::Seven, NodeIndex::new(5));
 
         assert_ne!(commitment1.hash(), commitment2.hash());
     }
@@ -218,7 +382,7 @@ mod tests {
     #[test]
     fn test_cloning_behavior() {
         // Test that cloning works correctly
-        let (commitment, key) = Commitment::new(Value::Four, 10);
+        let (commitment, key) = Commitment::new(Value::Four, NodeIndex::new(10));
         let cloned_commitment = commitment.clone();
 
         // Original should still work
@@ -234,11 +398,11 @@ mod tests {
     fn test_hash_verification() {
         let value = Value::Nine;
         let nonce: Bytes = vec![1, 2, 3, 4, 5].into();
-        let hash = compute_hash(value, &nonce);
+        let hash = compute_hash(value, 99, &nonce);
 
         // Create a commitment with same parameters
         let commitment = Commitment::<Hidden> {
-            hash: hash.clone(),
+            hash: CommitmentHash::new(hash.clone()),
             node_id: 99,
             key: None,
             _marker: PhantomData,
@@ -272,8 +436,9 @@ mod tests {
         let nonce = vec![7, 8, 9, 10];
 
         // Computing the same hash twice should yield the same result
-        let hash1 = compute_hash(value, &nonce);
-        let hash2 = compute_hash(value, &nonce);
+        let hash1 = compute_hash(value, 4, &nonce);
+        let hash2 = compute_hash(value, 4, &nonce);
         assert_eq!(hash1, hash2);
+        assert_ne!(hash1, compute_hash(value, 5, &nonce));
     }
 }
@@ -0,0 +1,89 @@
+use std::fmt;
+
+use bytes::Bytes;
+
+/// The hash a [`Commitment`](crate::Commitment) is made to.
+/// Kept distinct from [`PuzzleDigest`] so one can't be passed where the other is expected.
+///
+/// ```compile_fail
+/// use petgraph::graph::NodeIndex;
+/// use zk_sudoku_prover::{Commitment, PuzzleDigest, Value};
+///
+/// fn check_puzzle(_: &PuzzleDigest) {}
+/// let (commitment, _) = Commitment::new(Value::One, NodeIndex::new(0));
+/// check_puzzle(commitment.hash());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CommitmentHash(Bytes);
+
+/// Blake3 digest identifying a public puzzle, see [`ProofInput::puzzle_digest`](crate::ProofInput::puzzle_digest)
+///
+/// ```compile_fail
+/// use std::str::FromStr;
+/// use zk_sudoku_prover::{CommitmentHash, ProofInput, SudokuGrid};
+///
+/// fn check_commitment(_: &CommitmentHash) {}
+/// let grid = SudokuGrid::from_str(
+///     "296541378851273694743698251915764832387152946624839517139486725478325169562917483",
+/// )
+/// .unwrap();
+/// let input = ProofInput::from_solution(&grid).unwrap();
+/// check_commitment(&input.puzzle_digest());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PuzzleDigest(Bytes);
+
+impl CommitmentHash {
+    pub fn new(bytes: Bytes) -> Self {
+        Self(bytes)
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl PuzzleDigest {
+    pub fn new(bytes: Bytes) -> Self {
+        Self(bytes)
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+fn write_hex(f: &mut fmt::Formatter<'_>, bytes: &[u8]) -> fmt::Result {
+    for byte in bytes {
+        write!(f, "{byte:02x}")?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for CommitmentHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_hex(f, &self.0)
+    }
+}
+
+impl fmt::Display for PuzzleDigest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_hex(f, &self.0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_hex_display() {
+        let hash = CommitmentHash::new(Bytes::from_static(&[0x00, 0x0f, 0xab, 0xff]));
+        assert_eq!(hash.to_string(), "000fabff");
+        assert_eq!(hash.as_bytes(), &[0x00, 0x0f, 0xab, 0xff]);
+
+        let digest = PuzzleDigest::new(Bytes::from_static(&[0x12, 0x34]));
+        assert_eq!(digest.to_string(), "1234");
+        assert_eq!(digest.as_bytes(), &[0x12, 0x34]);
+    }
+}
@@ -1,4 +1,6 @@
 mod colour_shuffle;
 mod commitment;
+mod digest;
 pub use colour_shuffle::*;
 pub use commitment::*;
+pub use digest::*;
@@ -1,6 +1,13 @@
+#[cfg(feature = "render")]
+mod render;
+
+use std::collections::HashMap;
+
+use itertools::Itertools;
 use petgraph::graph::{EdgeIndex, EdgeIndices, NodeIndex, UnGraph};
+use petgraph::visit::EdgeRef;
 
-use crate::{Cell, Point, Position, SudokuGrid, Value};
+use crate::{BitSet9, Cell, EdgeNodeMap, Point, Position, SudokuGrid, Value};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct SudokuNode {
@@ -8,12 +15,21 @@ pub struct SudokuNode {
     location: Point,
 }
 
+/// The 81 cell nodes are always added first, so their indices are `0..81`.
+const CELL_NODE_COUNT: usize = 81;
+
+/// Whether the node is a clique node, its location and its value
+type NodeKey = (bool, Point, Value);
+
 /// This graph is a colouring problem representation of a sudoku grid.
 /// Each Node should be connected to all other nodes in the same row, column, box & also to clues.
 /// The clues are not connected to each other but to a clique set of nine special nodes each corresponding to a number.
 /// The clue node are forced to have a particular value by connecting it to all the clique nodes except the one corresponding to its value.
+/// A grid without any hints has nothing to anchor, so its graph is a pure colouring graph of the 81 cells with no clique nodes.
 pub struct Graph {
     pub graph: UnGraph<SudokuNode, ()>,
+    // Node of every cell, recorded while building
+    point_to_node: HashMap<Point, NodeIndex>,
 }
 
 impl Graph {
@@ -22,13 +38,14 @@ impl Graph {
 
         // Create nodes for each cell in the grid
         let mut cell_nodes = [[NodeIndex::new(0); 9]; 9];
+        let mut point_to_node = HashMap::with_capacity(CELL_NODE_COUNT);
         for x in Position::ALL_POSITIONS {
             for y in Position::ALL_POSITIONS {
                 let point = Point::new(x, y);
                 let cell = sudoku.get_cell(point);
 
                 // Use cell's value if it has one, otherwise default to One
-                let node_value = cell.value().unwrap();
+                let node_value = cell.value_or_default(Value::One);
 
                 let node_index = graph.add_node(SudokuNode {
                     cell: node_value,
@@ -36,17 +53,22 @@ impl Graph {
                 });
 
                 cell_nodes[x.to_index()][y.to_index()] = node_index;
+                point_to_node.insert(point, node_index);
             }
         }
 
-        // Create the 9 special clique nodes (one for each value 1-9)
+        // Create the 9 special clique nodes (one for each value 1-9), only needed to anchor hints
+        let has_hints =
+            Position::all_board_positions().any(|point| sudoku.get_cell(point).is_hint());
         let mut clique_nodes = Vec::with_capacity(9);
-        for i in Value::ALL_VALUES {
-            let node_index = graph.add_node(SudokuNode {
-                cell: i,
-                location: Point::default(), // Clique nodes don't have a grid location
-            });
-            clique_nodes.push(node_index);
+        if has_hints {
+            for i in Value::ALL_VALUES {
+                let node_index = graph.add_node(SudokuNode {
+                    cell: i,
+                    location: Point::default(), // Clique nodes don't have a grid location
+                });
+                clique_nodes.push(node_index);
+            }
         }
 
         // Connect cells in the same row
@@ -60,9 +82,10 @@ impl Graph {
 
         // Connect cells in the same column
         for col_idx in 0..9 {
+            let column = cell_nodes.map(|row| row[col_idx]);
             for i in 0..8 {
                 for j in (i + 1)..9 {
-                    graph.add_edge(cell_nodes[i][col_idx], cell_nodes[j][col_idx], ());
+                    graph.add_edge(column[i], column[j], ());
                 }
             }
         }
@@ -112,11 +135,47 @@ impl Graph {
             }
         }
 
-        Self { graph }
+        Self {
+            graph,
+            point_to_node,
+        }
+    }
+    /// The public constraint graph a verifier needs: hints are anchored to the clique nodes
+    /// as usual, but every other cell is treated as empty, so no guessed values end up on
+    /// the nodes. Its edges are identical to those of the prover's graph for the solution.
+    pub fn from_puzzle(puzzle: &SudokuGrid) -> Self {
+        Self::from_sudoku(&puzzle.hints())
+    }
+
+    /// Like [`Graph::from_sudoku`], but rejects grids whose hints contradict each other.
+    /// Two equal hints in a unit are adjacent nodes anchored to the same colour, so no
+    /// colouring of the resulting graph could ever be proper.
+    pub fn try_from_sudoku(sudoku: &SudokuGrid) -> Result<Self, GraphError> {
+        if !sudoku.hints().is_valid_solution() {
+            return Err(GraphError::ContradictoryHints);
+        }
+        Ok(Self::from_sudoku(sudoku))
     }
+
     pub fn node_count(&self) -> usize {
         self.graph.node_count()
     }
+    pub fn edge_count(&self) -> usize {
+        self.graph.edge_count()
+    }
+    /// The node of the cell at `point`
+    pub fn node_for_point(&self, point: Point) -> NodeIndex {
+        self.point_to_node[&point]
+    }
+
+    /// The grid location of a cell node, or `None` for a clique node or unknown index
+    pub fn node_point(&self, node: NodeIndex) -> Option<Point> {
+        if node.index() >= CELL_NODE_COUNT {
+            return None;
+        }
+        self.graph.node_weight(node).map(|node| node.location)
+    }
+
     /// Get all nodes in the graph
     pub fn nodes(&self) -> impl Iterator<Item = (NodeIndex, Value)> {
         self.graph
@@ -129,6 +188,158 @@ impl Graph {
         self.graph.edge_indices()
     }
 
+    /// Returns the induced subgraph on the nine cell nodes of one 3x3 box.
+    /// Parallel row/column/box edges are merged, so the result is the 36 edge box clique.
+    pub fn subgraph_for_box(&self, box_pos: Position) -> UnGraph<SudokuNode, ()> {
+        let points = box_pos.get_box_positions();
+        // Clique nodes have a default location, so only consider the cell nodes
+        let nodes: Vec<NodeIndex> = self
+            .graph
+            .node_indices()
+            .filter(|idx| {
+                idx.index() < CELL_NODE_COUNT && points.contains(&self.graph[*idx].location)
+            })
+            .collect();
+
+        let mut subgraph = UnGraph::with_capacity(9, 36);
+        let sub_nodes: Vec<NodeIndex> = nodes
+            .iter()
+            .map(|idx| subgraph.add_node(self.graph[*idx]))
+            .collect();
+        for (i, j) in (0..nodes.len()).tuple_combinations() {
+            if self.graph.contains_edge(nodes[i], nodes[j]) {
+                subgraph.add_edge(sub_nodes[i], sub_nodes[j], ());
+            }
+        }
+        subgraph
+    }
+
+    /// The public edge list a verifier needs: each edge and the two nodes it connects
+    pub fn edge_map(&self) -> EdgeNodeMap {
+        let mut edge_map = HashMap::with_capacity(self.edge_count());
+        for edge_ref in self.graph.edge_references() {
+            edge_map.insert(edge_ref.id(), (edge_ref.source(), edge_ref.target()));
+        }
+        edge_map
+    }
+
+    /// Symmetric adjacency matrix indexed by `NodeIndex`, for numeric analysis.
+    /// Parallel edges collapse into a single entry, so a cell pair sharing both a row and
+    /// a box is marked once.
+    pub fn to_adjacency_matrix(&self) -> Vec<Vec<bool>> {
+        let mut matrix = vec![vec![false; self.node_count()]; self.node_count()];
+        for edge_ref in self.graph.edge_references() {
+            let (a, b) = (edge_ref.source().index(), edge_ref.target().index());
+            matrix[a][b] = true;
+            matrix[b][a] = true;
+        }
+        matrix
+    }
+
+    /// The colouring stored on the nodes themselves: the grid's values for the cell nodes
+    /// and each clique node's own value. For a solved grid this is the solution's colouring.
+    pub fn natural_coloring(&self) -> HashMap<NodeIndex, Value> {
+        self.nodes().collect()
+    }
+
+    /// Whether `coloring` colours every node and gives the two ends of every edge different colours
+    pub fn is_properly_colored(&self, coloring: &HashMap<NodeIndex, Value>) -> bool {
+        self.graph.edge_references().all(|edge_ref| {
+            match (
+                coloring.get(&edge_ref.source()),
+                coloring.get(&edge_ref.target()),
+            ) {
+                (Some(a), Some(b)) => a != b,
+                _ => false,
+            }
+        })
+    }
+
+    /// Counts the proper colourings with the values 1-9, stopping once `cap` are found.
+    /// Clique nodes keep their own value, which fixes what each colour means, so for a
+    /// puzzle's graph this matches the number of solutions `SudokuGrid::solutions` finds.
+    /// Without hints nothing is anchored and every relabelling of a colouring counts.
+    pub fn count_proper_colorings(&self, cap: usize) -> usize {
+        if cap == 0 || self.has_self_loops() {
+            return 0;
+        }
+        let mut counter = ColouringCounter {
+            neighbours: self
+                .graph
+                .node_indices()
+                .map(|idx| {
+                    self.graph
+                        .neighbors(idx)
+                        .map(|n| n.index())
+                        .unique()
+                        .collect()
+                })
+                .collect(),
+            colours: self
+                .graph
+                .node_indices()
+                .map(|idx| (idx.index() >= CELL_NODE_COUNT).then(|| self.graph[idx].cell))
+                .collect(),
+            cap,
+            found: 0,
+        };
+        // The anchored clique nodes must not clash with each other
+        let anchored_clash =
+            (CELL_NODE_COUNT..self.node_count()).any(|node| counter.candidates(node).is_none());
+        if !anchored_clash {
+            counter.search();
+        }
+        counter.found
+    }
+
+    /// Whether any edge connects a node to itself. Such an edge can never be coloured properly.
+    pub fn has_self_loops(&self) -> bool {
+        self.graph
+            .edge_references()
+            .any(|edge_ref| edge_ref.source() == edge_ref.target())
+    }
+
+    /// Whether both graphs have the same nodes and edges, regardless of index order.
+    /// Nodes are identified by their location and value, with clique nodes (always added
+    /// after the 81 cells) told apart from the cell at the origin; edges are compared as unordered pairs, counting duplicates.
+    pub fn structurally_eq(&self, other: &Graph) -> bool {
+        if self.node_count() != other.node_count() || self.edge_count() != other.edge_count() {
+            return false;
+        }
+        let mut nodes = self.node_keys();
+        let mut other_nodes = other.node_keys();
+        nodes.sort_unstable();
+        other_nodes.sort_unstable();
+        nodes == other_nodes && self.edge_keys() == other.edge_keys()
+    }
+
+    // Index independent identity of each node, in index order
+    fn node_keys(&self) -> Vec<NodeKey> {
+        self.graph
+            .node_indices()
+            .map(|idx| {
+                let node = self.graph[idx];
+                (idx.index() >= CELL_NODE_COUNT, node.location, node.cell)
+            })
+            .collect()
+    }
+
+    // Every edge as a sorted pair of node keys, sorted
+    fn edge_keys(&self) -> Vec<(NodeKey, NodeKey)> {
+        let keys = self.node_keys();
+        let mut edges: Vec<_> = self
+            .graph
+            .edge_references()
+            .map(|edge_ref| {
+                let a = keys[edge_ref.source().index()];
+                let b = keys[edge_ref.target().index()];
+                (a.min(b), a.max(b))
+            })
+            .collect();
+        edges.sort_unstable();
+        edges
+    }
+
     /// Get the nodes connected by an edge
     pub fn get_edge_nodes(&self, edge: EdgeIndex) -> Result<(NodeIndex, NodeIndex), GraphError> {
         let (a, b) = self
@@ -139,8 +350,337 @@ impl Graph {
     }
 }
 
+/// Backtracking search over node colours that always branches on the most constrained node.
+struct ColouringCounter {
+    neighbours: Vec<Vec<usize>>,
+    colours: Vec<Option<Value>>,
+    cap: usize,
+    found: usize,
+}
+
+impl ColouringCounter {
+    fn search(&mut self) {
+        let Some((node, candidates)) = self.most_constrained() else {
+            // Every node is coloured
+            self.found += 1;
+            return;
+        };
+        for value in candidates.iter() {
+            self.colours[node] = Some(value);
+            self.search();
+            if self.found >= self.cap {
+                break;
+            }
+        }
+        self.colours[node] = None;
+    }
+
+    /// Colours left for `node` by its coloured neighbours, or `None` if its own colour clashes
+    fn candidates(&self, node: usize) -> Option<BitSet9> {
+        let mut candidates = BitSet9::full();
+        for &neighbour in &self.neighbours[node] {
+            if let Some(value) = self.colours[neighbour] {
+                if self.colours[node] == Some(value) {
+                    return None;
+                }
+                candidates.remove(value);
+            }
+        }
+        Some(candidates)
+    }
+
+    /// The uncoloured node with the fewest candidates, or `None` once every node is coloured
+    fn most_constrained(&self) -> Option<(usize, BitSet9)> {
+        let mut best: Option<(usize, BitSet9)> = None;
+        for node in (0..self.colours.len()).filter(|&node| self.colours[node].is_none()) {
+            let candidates = self.candidates(node).unwrap_or_default();
+            if candidates.len() <= 1 {
+                // Can't do better than a forced colour or a dead end
+                return Some((node, candidates));
+            }
+            if best.is_none_or(|(_, b)| candidates.len() < b.len()) {
+                best = Some((node, candidates));
+            }
+        }
+        best
+    }
+}
+
+impl Default for Graph {
+    /// The colouring graph of an all-empty grid: every row, column and box constraint,
+    /// but no clue anchoring.
+    fn default() -> Self {
+        Self::from_sudoku(&SudokuGrid::new())
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum GraphError {
     #[error("Edge not found")]
     EdgeNotFound,
+    #[error("Hints contradict each other")]
+    ContradictoryHints,
+    #[cfg(feature = "render")]
+    #[error("Failed to render graph")]
+    Render(#[from] std::fmt::Error),
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_default_graph() {
+        let graph = Graph::default();
+
+        // 3 * 9 units * 36 pairs, where cells sharing a row or column and a box get two edges
+        assert_eq!(graph.edge_count(), 972);
+        let unique_pairs: std::collections::HashSet<_> = graph
+            .edges()
+            .map(|edge| {
+                let (a, b) = graph.get_edge_nodes(edge).unwrap();
+                (a.min(b), a.max(b))
+            })
+            .collect();
+        assert_eq!(unique_pairs.len(), 810);
+
+        // No hints, so nothing is wired to the clique nodes
+        let clue_edges = unique_pairs
+            .iter()
+            .filter(|(a, b)| a.index() >= CELL_NODE_COUNT || b.index() >= CELL_NODE_COUNT)
+            .count();
+        assert_eq!(clue_edges, 0);
+    }
+
+    #[test]
+    fn test_structurally_eq() {
+        let mut grid = SudokuGrid::test_solution();
+        grid.set_cell(
+            Point::default(),
+            Cell::Hint(grid.get_cell(Point::default()).value().unwrap()),
+        );
+        let graph = Graph::from_sudoku(&grid);
+        assert!(graph.structurally_eq(&Graph::from_sudoku(&grid)));
+
+        // The same cell graph with its nodes added in reverse order and edges flipped
+        let cells_only = Graph::from_sudoku(&SudokuGrid::test_solution());
+        let mut reversed = UnGraph::new_undirected();
+        for idx in cells_only.graph.node_indices().rev() {
+            reversed.add_node(cells_only.graph[idx]);
+        }
+        let flip = |node: NodeIndex| NodeIndex::new(CELL_NODE_COUNT - 1 - node.index());
+        for edge_ref in cells_only
+            .graph
+            .edge_references()
+            .collect::<Vec<_>>()
+            .into_iter()
+            .rev()
+        {
+            reversed.add_edge(flip(edge_ref.target()), flip(edge_ref.source()), ());
+        }
+        let reversed = Graph {
+            graph: reversed,
+            ..Graph::default()
+        };
+        assert!(cells_only.structurally_eq(&reversed));
+
+        // Different grids give different graphs
+        assert!(!graph.structurally_eq(&cells_only));
+        assert!(!cells_only.structurally_eq(&Graph::default()));
+    }
+
+    #[test]
+    fn test_node_for_point() {
+        let mut grid = SudokuGrid::test_solution();
+        grid.set_cell(
+            Point::default(),
+            Cell::Hint(grid.get_cell(Point::default()).value().unwrap()),
+        );
+        let graph = Graph::from_sudoku(&grid);
+
+        for point in Position::all_board_positions() {
+            let node = graph.node_for_point(point);
+            assert_eq!(graph.node_point(node), Some(point));
+            assert_eq!(
+                node.index(),
+                point.x().to_index() * 9 + point.y().to_index()
+            );
+        }
+        // Clique nodes have no grid location
+        assert_eq!(graph.node_point(NodeIndex::new(CELL_NODE_COUNT)), None);
+        assert_eq!(graph.node_point(NodeIndex::new(1000)), None);
+    }
+
+    #[test]
+    fn test_contradictory_hints_rejected() {
+        // Two 5s in the first row
+        let mut grid = SudokuGrid::new();
+        grid.set_cell(Point::new(Position::ONE, Position::ONE), Cell::new_hint(5));
+        grid.set_cell(Point::new(Position::ONE, Position::NINE), Cell::new_hint(5));
+        assert!(matches!(
+            Graph::try_from_sudoku(&grid),
+            Err(GraphError::ContradictoryHints)
+        ));
+
+        // Guesses can't make the clues infeasible, so a clashing guess is left to the prover
+        grid.set_cell(
+            Point::new(Position::ONE, Position::NINE),
+            Cell::new_guess(5),
+        );
+        assert!(Graph::try_from_sudoku(&grid).is_ok());
+        assert!(Graph::try_from_sudoku(&SudokuGrid::test_solution()).is_ok());
+    }
+
+    #[test]
+    fn test_no_clique_nodes_without_hints() {
+        let graph = Graph::from_sudoku(&SudokuGrid::new());
+        assert_eq!(graph.node_count(), CELL_NODE_COUNT);
+
+        // Guesses don't need anchoring either
+        let graph = Graph::from_sudoku(&SudokuGrid::test_solution());
+        assert_eq!(graph.node_count(), CELL_NODE_COUNT);
+
+        let mut grid = SudokuGrid::test_solution();
+        let point = Point::new(Position::ONE, Position::ONE);
+        grid.set_cell(point, Cell::Hint(grid.get_cell(point).value().unwrap()));
+        let graph = Graph::from_sudoku(&grid);
+        assert_eq!(graph.node_count(), CELL_NODE_COUNT + 9);
+        // The hint is tied to the eight clique nodes of the other values
+        assert_eq!(graph.edge_count(), 972 + 8);
+    }
+
+    #[test]
+    fn test_from_puzzle_matches_prover_graph() {
+        let solution = SudokuGrid::test_solution();
+        // Keep every third cell as a clue
+        let puzzle = solution.keep_every(3);
+        let input = crate::ProofInput::new(&puzzle, &solution).unwrap();
+
+        let prover_graph = Graph::from_sudoku(input.solution());
+        // Passing the solution itself must not leak its guesses either
+        for public in [input.puzzle(), input.solution()] {
+            let verifier_graph = Graph::from_puzzle(public);
+            assert_eq!(verifier_graph.edge_map(), prover_graph.edge_map());
+            assert_eq!(verifier_graph.node_count(), prover_graph.node_count());
+
+            for (node, value) in verifier_graph.nodes().take(CELL_NODE_COUNT) {
+                let location = verifier_graph.graph[node].location;
+                match input.puzzle().get_cell(location).value() {
+                    Some(hint) => assert_eq!(value, hint),
+                    None => assert_eq!(value, Value::One),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_count_proper_colorings() {
+        let solution = SudokuGrid::test_solution();
+        let clues_where = |keep: &dyn Fn(usize) -> bool| {
+            let mut puzzle = SudokuGrid::new();
+            for (i, point) in Position::all_board_positions().enumerate() {
+                if keep(i) {
+                    let value = solution.get_cell(point).value().unwrap();
+                    puzzle.set_cell(point, Cell::Hint(value));
+                }
+            }
+            puzzle
+        };
+
+        // Every other cell as a clue leaves a unique solution
+        let unique = clues_where(&|i| i % 2 == 0);
+        assert_eq!(unique.solutions(10).len(), 1);
+        assert_eq!(Graph::from_puzzle(&unique).count_proper_colorings(10), 1);
+
+        // A rectangle of 9s and 4s spanning two boxes can be filled either way round
+        let ambiguous = clues_where(&|i| ![1, 4, 2 * 9 + 1, 2 * 9 + 4].contains(&i));
+        assert_eq!(ambiguous.solutions(10).len(), 2);
+        let graph = Graph::from_puzzle(&ambiguous);
+        assert_eq!(graph.count_proper_colorings(10), 2);
+        assert_eq!(graph.count_proper_colorings(1), 1);
+        assert_eq!(graph.count_proper_colorings(0), 0);
+
+        // Nothing anchors the colours of an empty grid
+        assert_eq!(Graph::default().count_proper_colorings(5), 5);
+    }
+
+    #[test]
+    fn test_to_adjacency_matrix() {
+        // A triangle with a tail, free of parallel edges
+        let mut small = UnGraph::new_undirected();
+        let nodes: Vec<_> = Value::ALL_VALUES[..4]
+            .iter()
+            .map(|&cell| {
+                small.add_node(SudokuNode {
+                    cell,
+                    location: Point::default(),
+                })
+            })
+            .collect();
+        for (a, b) in [(0, 1), (1, 2), (2, 0), (2, 3)] {
+            small.add_edge(nodes[a], nodes[b], ());
+        }
+        let graph = Graph {
+            graph: small,
+            point_to_node: HashMap::new(),
+        };
+
+        let matrix = graph.to_adjacency_matrix();
+        assert_eq!(matrix.len(), 4);
+        for (i, row) in matrix.iter().enumerate() {
+            assert!(!row[i]);
+            for (j, &adjacent) in row.iter().enumerate() {
+                assert_eq!(adjacent, matrix[j][i]);
+            }
+        }
+        let marked = matrix
+            .iter()
+            .flatten()
+            .filter(|&&adjacent| adjacent)
+            .count();
+        assert_eq!(marked, 2 * graph.edge_count());
+        assert!(matrix[2][3] && !matrix[0][3]);
+
+        // The full graph's parallel edges only count once
+        let marked = Graph::default()
+            .to_adjacency_matrix()
+            .into_iter()
+            .flatten()
+            .filter(|&adjacent| adjacent)
+            .count();
+        assert_eq!(marked, 2 * 810);
+    }
+
+    #[test]
+    fn test_subgraph_for_box() {
+        let grid = SudokuGrid::test_solution();
+        let graph = Graph::from_sudoku(&grid);
+
+        for box_pos in Position::ALL_POSITIONS {
+            let subgraph = graph.subgraph_for_box(box_pos);
+            assert_eq!(subgraph.node_count(), 9);
+            assert_eq!(subgraph.edge_count(), 36);
+
+            let points = box_pos.get_box_positions();
+            for node in subgraph.node_weights() {
+                assert!(points.contains(&node.location));
+                assert_eq!(Some(node.cell), grid.get_cell(node.location).value());
+            }
+        }
+    }
+
+    #[test]
+    fn test_natural_coloring() {
+        let graph = Graph::from_sudoku(&SudokuGrid::test_solution());
+        let coloring = graph.natural_coloring();
+        assert_eq!(coloring.len(), graph.node_count());
+        assert!(graph.is_properly_colored(&coloring));
+
+        // Empty cells are coloured One, so rows with several gaps clash
+        let mut grid = SudokuGrid::test_solution();
+        grid.set_cell(Point::new(Position::ONE, Position::ONE), Cell::Empty);
+        grid.set_cell(Point::new(Position::ONE, Position::TWO), Cell::Empty);
+        let graph = Graph::from_sudoku(&grid);
+        assert!(!graph.is_properly_colored(&graph.natural_coloring()));
+    }
 }
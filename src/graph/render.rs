@@ -0,0 +1,106 @@
+use std::fmt::Write;
+
+use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
+
+use super::{CELL_NODE_COUNT, Graph, GraphError};
+
+/// Distance between neighbouring nodes, in SVG user units
+const SPACING: usize = 40;
+const RADIUS: usize = 12;
+
+impl Graph {
+    /// Render the graph as an SVG document. Cell nodes sit at their grid position, row by
+    /// row, and the clique nodes, if any, get a row of their own below the board.
+    /// Nodes are labelled with their values, so don't publish a rendering of a prover's graph.
+    pub fn to_svg(&self) -> Result<String, GraphError> {
+        let has_cliques = self.node_count() > CELL_NODE_COUNT;
+        let width = 9 * SPACING;
+        let height = if has_cliques {
+            11 * SPACING
+        } else {
+            9 * SPACING
+        };
+
+        let mut svg = String::new();
+        writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#
+        )?;
+
+        writeln!(svg, r#"<g stroke="grey" stroke-width="0.5">"#)?;
+        for edge_ref in self.graph.edge_references() {
+            let (x1, y1) = self.node_centre(edge_ref.source());
+            let (x2, y2) = self.node_centre(edge_ref.target());
+            writeln!(svg, r#"<line x1="{x1}" y1="{y1}" x2="{x2}" y2="{y2}"/>"#)?;
+        }
+        writeln!(svg, "</g>")?;
+
+        writeln!(
+            svg,
+            r#"<g font-family="sans-serif" font-size="12" text-anchor="middle">"#
+        )?;
+        for (node, value) in self.nodes() {
+            let (cx, cy) = self.node_centre(node);
+            let fill = if node.index() < CELL_NODE_COUNT {
+                "white"
+            } else {
+                "lightgrey"
+            };
+            writeln!(
+                svg,
+                r#"<circle cx="{cx}" cy="{cy}" r="{RADIUS}" fill="{fill}" stroke="black"/>"#
+            )?;
+            writeln!(svg, r#"<text x="{cx}" y="{}">{value}</text>"#, cy + 4)?;
+        }
+        writeln!(svg, "</g>")?;
+        writeln!(svg, "</svg>")?;
+        Ok(svg)
+    }
+
+    fn node_centre(&self, node: NodeIndex) -> (usize, usize) {
+        let offset = SPACING / 2;
+        if node.index() < CELL_NODE_COUNT {
+            let location = self.graph[node].location;
+            (
+                offset + location.y().to_index() * SPACING,
+                offset + location.x().to_index() * SPACING,
+            )
+        } else {
+            // Leave an empty row between the board and the clique nodes
+            let clique = node.index() - CELL_NODE_COUNT;
+            (offset + clique * SPACING, offset + 10 * SPACING)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Cell, Point, Position, SudokuGrid};
+
+    use super::*;
+
+    #[test]
+    fn test_to_svg() {
+        let mut grid = SudokuGrid::new();
+        grid.set_cell(Point::default(), Cell::new_hint(5));
+        let graph = Graph::from_sudoku(&grid);
+        let svg = graph.to_svg().unwrap();
+
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        assert_eq!(svg.matches("<g ").count(), svg.matches("</g>").count());
+        assert_eq!(svg.matches("<circle ").count(), graph.node_count());
+        assert_eq!(svg.matches("<line ").count(), graph.edge_count());
+
+        // Without hints there is no clique row
+        let svg = Graph::default().to_svg().unwrap();
+        assert_eq!(svg.matches("<circle ").count(), CELL_NODE_COUNT);
+        assert!(svg.contains(r#"height="360""#));
+
+        // Cells are laid out by their grid position
+        let corner = Point::new(Position::NINE, Position::ONE);
+        let node = NodeIndex::new(corner.x().to_index() * 9 + corner.y().to_index());
+        assert_eq!(graph.node_centre(node), (20, 340));
+    }
+}
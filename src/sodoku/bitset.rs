@@ -0,0 +1,90 @@
+use super::Value;
+
+/// A set of sudoku values packed into the low nine bits of a `u16`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct BitSet9 {
+    bits: u16,
+}
+
+impl BitSet9 {
+    const ALL_BITS: u16 = 0b1_1111_1111;
+
+    /// The empty set
+    pub fn new() -> Self {
+        Self { bits: 0 }
+    }
+
+    /// The set of all nine values
+    pub fn full() -> Self {
+        Self {
+            bits: Self::ALL_BITS,
+        }
+    }
+
+    pub fn insert(&mut self, value: Value) {
+        self.bits |= Self::bit(value);
+    }
+
+    pub fn remove(&mut self, value: Value) {
+        self.bits &= !Self::bit(value);
+    }
+
+    pub fn contains(&self, value: Value) -> bool {
+        self.bits & Self::bit(value) != 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.bits.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits == 0
+    }
+
+    /// The values in the set, in ascending order
+    pub fn iter(&self) -> impl Iterator<Item = Value> + '_ {
+        Value::ALL_VALUES
+            .into_iter()
+            .filter(|value| self.contains(*value))
+    }
+
+    fn bit(value: Value) -> u16 {
+        1 << value.to_index()
+    }
+}
+
+impl FromIterator<Value> for BitSet9 {
+    fn from_iter<I: IntoIterator<Item = Value>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for value in iter {
+            set.insert(value);
+        }
+        set
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_bitset() {
+        let mut set = BitSet9::new();
+        assert!(set.is_empty());
+
+        set.insert(Value::Three);
+        set.insert(Value::Nine);
+        set.insert(Value::Three);
+        assert_eq!(set.len(), 2);
+        assert!(set.contains(Value::Nine));
+        assert!(!set.contains(Value::One));
+
+        set.remove(Value::Nine);
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![Value::Three]);
+
+        let full = BitSet9::full();
+        assert_eq!(full.len(), 9);
+        assert_eq!(full.iter().collect::<Vec<_>>(), Value::ALL_VALUES.to_vec());
+        assert_eq!(Value::ALL_VALUES.into_iter().collect::<BitSet9>(), full);
+    }
+}
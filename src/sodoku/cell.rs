@@ -1,6 +1,7 @@
 use super::Value;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Cell {
     #[default]
     Empty,
@@ -42,6 +43,21 @@ impl Cell {
             Cell::Hint(val) => Some(*val),
         }
     }
+
+    /// The cell's value, or `default` for an empty cell
+    pub fn value_or_default(&self, default: Value) -> Value {
+        self.value().unwrap_or(default)
+    }
+
+    /// Applies `f` to the cell's value, keeping it a hint, guess or empty cell
+    pub fn map_value(self, f: impl FnOnce(Value) -> Value) -> Cell {
+        match self {
+            Cell::Empty => Cell::Empty,
+            Cell::Guess(val) => Cell::Guess(f(val)),
+            Cell::Hint(val) => Cell::Hint(f(val)),
+        }
+    }
+
     // Add a method for the allocation-free Set validation
     pub fn value_as_index(&self) -> Option<usize> {
         self.value().map(|v| v.to_numeric() as usize)
@@ -79,6 +95,19 @@ impl Cell {
     }
 }
 
+/// Orders cells by value, with empty cells first. A guess sorts before a hint of the same value.
+impl Ord for Cell {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (self.value(), self.is_hint()).cmp(&(other.value(), other.is_hint()))
+    }
+}
+
+impl PartialOrd for Cell {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl std::fmt::Display for Cell {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -88,3 +117,51 @@ impl std::fmt::Display for Cell {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_ordering() {
+        let mut cells = vec![
+            Cell::new_hint(5),
+            Cell::new_guess(9),
+            Cell::Empty,
+            Cell::new_guess(5),
+            Cell::new_hint(1),
+            Cell::Empty,
+        ];
+        cells.sort();
+        assert_eq!(
+            cells,
+            vec![
+                Cell::Empty,
+                Cell::Empty,
+                Cell::new_hint(1),
+                Cell::new_guess(5),
+                Cell::new_hint(5),
+                Cell::new_guess(9),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_value_or_default() {
+        assert_eq!(Cell::Empty.value_or_default(Value::Seven), Value::Seven);
+        assert_eq!(
+            Cell::new_guess(2).value_or_default(Value::Seven),
+            Value::Two
+        );
+        assert_eq!(Cell::new_hint(9).value_or_default(Value::One), Value::Nine);
+    }
+
+    #[test]
+    fn test_map_value() {
+        let next = |v: Value| Value::from_index((v.to_index() + 1) % 9);
+
+        assert_eq!(Cell::Empty.map_value(next), Cell::Empty);
+        assert_eq!(Cell::new_hint(3).map_value(next), Cell::new_hint(4));
+        assert_eq!(Cell::new_guess(9).map_value(next), Cell::new_guess(1));
+    }
+}
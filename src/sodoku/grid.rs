@@ -1,8 +1,14 @@
-use std::{fmt, str::FromStr};
+use std::{collections::HashMap, fmt, str::FromStr};
 
-use super::{Box, Cell, Column, Point, Position, Row, Set};
+use itertools::Itertools;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+use crate::ColourShuffle;
+
+use super::{Box, Cell, Column, Point, Position, Row, Set, SetType, Unit, UnitKind, Value};
+
+/// A 9x9 board of cells.
+/// Equality and hashing compare cells exactly, so a hint and a guess of the same value differ.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 pub struct SudokuGrid {
     cells: [[Cell; 9]; 9],
 }
@@ -14,10 +20,54 @@ impl SudokuGrid {
         }
     }
 
+    /// Builds a grid from a map of flat row-major index (0-80) to digit (1-9), leaving every
+    /// other cell empty. Clues become guesses, exactly as `from_str` would parse them.
+    pub fn from_clue_map(clues: &HashMap<usize, u8>) -> Result<Self, SudokuParseError> {
+        let mut cells = [[Cell::Empty; 9]; 9];
+        for (&index, &digit) in clues {
+            if index >= 81 {
+                return Err(SudokuParseError::IndexOutOfRange(index));
+            }
+            if !(1..=9).contains(&digit) {
+                return Err(SudokuParseError::InvalidDigit { index, digit });
+            }
+            cells[index / 9][index % 9] = Cell::Guess(Value::from(digit));
+        }
+        Ok(Self { cells })
+    }
+
+    /// Parses a puzzle and its solution together: the puzzle's digits become `Cell::Hint`s
+    /// and every other digit of the solution a `Cell::Guess`, so the clues stay public
+    /// through to the graph. Both strings use the `from_str` format and must agree on every
+    /// clue.
+    pub fn from_puzzle_and_solution(
+        puzzle: &str,
+        solution: &str,
+    ) -> Result<Self, SudokuParseError> {
+        let puzzle = Self::from_str(puzzle)?;
+        let mut grid = Self::from_str(solution)?;
+        for (index, point) in Position::all_board_positions().enumerate() {
+            let Some(clue) = puzzle.get_cell(point).value() else {
+                continue;
+            };
+            if grid.get_cell(point).value() != Some(clue) {
+                return Err(SudokuParseError::SolutionMismatch { index });
+            }
+            grid.set_cell(point, Cell::Hint(clue));
+        }
+        Ok(grid)
+    }
+
+    /// The cell at `pos`
     pub fn get_cell(&self, pos: Point) -> Cell {
         self.cells[pos]
     }
 
+    /// Replace the cell at `pos`
+    pub fn set_cell(&mut self, pos: Point, cell: Cell) {
+        self.cells[pos] = cell;
+    }
+
     pub fn get_row(&self, row: Position) -> Set<Row> {
         Set::new(self.cells[row], row)
     }
@@ -39,6 +89,154 @@ impl SudokuGrid {
         Set::new(new_square, pos)
     }
 
+    /// Gets any kind of unit generically, e.g. `grid.get_set::<Row>(pos)`
+    pub fn get_set<T: SetType>(&self, pos: Position) -> Set<T> {
+        T::get_set(self, pos)
+    }
+
+    /// All 27 units of the grid: the nine rows, then the nine columns, then the nine boxes
+    pub fn units(&self) -> impl Iterator<Item = Unit> + '_ {
+        let rows = Position::ALL_POSITIONS.map(|pos| Unit::Row(self.get_row(pos)));
+        let columns = Position::ALL_POSITIONS.map(|pos| Unit::Column(self.get_column(pos)));
+        let squares = Position::ALL_POSITIONS.map(|pos| Unit::Box(self.get_square(pos)));
+        rows.into_iter().chain(columns).chain(squares)
+    }
+
+    /// Lists every position where the two grids differ, with this grid's cell first.
+    pub fn diff(&self, other: &SudokuGrid) -> Vec<(Point, Cell, Cell)> {
+        Position::all_board_positions()
+            .filter_map(|point| {
+                let (ours, theirs) = (self.cells[point], other.cells[point]);
+                (ours != theirs).then_some((point, ours, theirs))
+            })
+            .collect()
+    }
+
+    /// The grid mirrored along its main diagonal, so rows become columns
+    pub fn transpose(&self) -> Self {
+        let mut grid = *self;
+        grid.transpose_in_place();
+        grid
+    }
+
+    /// The grid rotated a quarter turn clockwise
+    pub fn rotate90(&self) -> Self {
+        let mut grid = *self;
+        grid.rotate90_in_place();
+        grid
+    }
+
+    /// The grid with every value mapped through `shuffle`, keeping each cell's kind
+    pub fn relabel(&self, shuffle: &ColourShuffle) -> Self {
+        let mut grid = *self;
+        grid.relabel_in_place(shuffle);
+        grid
+    }
+
+    pub fn transpose_in_place(&mut self) {
+        for row in 0..9 {
+            for col in (row + 1)..9 {
+                let cell = self.cells[row][col];
+                self.cells[row][col] = self.cells[col][row];
+                self.cells[col][row] = cell;
+            }
+        }
+    }
+
+    pub fn rotate90_in_place(&mut self) {
+        // A clockwise quarter turn is a transpose followed by mirroring each row
+        self.transpose_in_place();
+        for row in &mut self.cells {
+            row.reverse();
+        }
+    }
+
+    pub fn relabel_in_place(&mut self, shuffle: &ColourShuffle) {
+        for cell in self.cells.as_flattened_mut() {
+            *cell = cell.map_value(|value| shuffle.apply(value));
+        }
+    }
+
+    /// A copy of the grid keeping only its hints, every other cell empty
+    pub fn hints(&self) -> Self {
+        let mut hints = *self;
+        hints.clear_guesses();
+        hints
+    }
+
+    /// A copy keeping every `n`th cell in row-major order, starting from the first, with every
+    /// other cell empty. Handy for carving a puzzle out of a solved board.
+    /// Panics if `n` is zero, as there is no such step.
+    pub fn keep_every(&self, n: usize) -> Self {
+        assert!(n > 0, "keep_every needs a step of at least 1");
+        let mut puzzle = Self::new();
+        for point in Position::all_board_positions().step_by(n) {
+            puzzle.cells[point] = self.cells[point];
+        }
+        puzzle
+    }
+
+    /// Whether every hint of this grid holds the solution's value at the same point,
+    /// i.e. this puzzle is a legitimate reduction of `solution`. Guesses are ignored.
+    pub fn hints_match(&self, solution: &SudokuGrid) -> bool {
+        Position::all_board_positions().all(|point| match self.cells[point] {
+            Cell::Hint(value) => solution.cells[point].value() == Some(value),
+            _ => true,
+        })
+    }
+
+    /// Reset the board to its clue state, emptying every guess but keeping the hints
+    pub fn clear_guesses(&mut self) {
+        for cell in self.cells.as_flattened_mut() {
+            if cell.is_guess() {
+                *cell = Cell::Empty;
+            }
+        }
+    }
+
+    /// Number of filled cells, i.e. the clues when the grid is an unsolved puzzle
+    pub fn clue_count(&self) -> usize {
+        Position::all_board_positions()
+            .filter(|point| self.cells[*point].is_filled())
+            .count()
+    }
+
+    /// Fraction of the board that is filled, counting hints and guesses alike
+    pub fn completion_ratio(&self) -> f64 {
+        self.clue_count() as f64 / 81.0
+    }
+
+    /// Whether every cell holds a value
+    pub fn is_full(&self) -> bool {
+        self.clue_count() == 81
+    }
+
+    /// Every value repeated within a row, column or box, in the order of [`SudokuGrid::units`].
+    /// Unlike [`SudokuGrid::is_valid_solution`] this says which units are broken.
+    pub fn validate(&self) -> GridValidation {
+        let conflicts: Vec<Conflict> = self
+            .units()
+            .flat_map(|unit| {
+                let repeated: Vec<Value> = unit
+                    .cells()
+                    .iter()
+                    .filter_map(|cell| cell.value())
+                    .duplicates()
+                    .collect();
+                repeated.into_iter().map(move |value| Conflict {
+                    unit: unit.kind(),
+                    position: unit.position(),
+                    value,
+                })
+            })
+            .collect();
+        if conflicts.is_empty() {
+            Ok(())
+        } else {
+            Err(conflicts)
+        }
+    }
+
     pub fn is_valid_solution(&self) -> bool {
         for row in Position::ALL_POSITIONS {
             if !self.get_row(row).is_valid() {
@@ -59,15 +257,46 @@ impl SudokuGrid {
     }
 }
 
+#[cfg(test)]
+impl SudokuGrid {
+    /// A solved board shared by the tests, its cells all guesses
+    pub(crate) fn test_solution() -> Self {
+        Self::from_str(
+            "296541378851273694743698251915764832387152946624839517139486725478325169562917483",
+        )
+        .unwrap()
+    }
+}
+
+/// Iterates the cells in row-major order
+impl<'a> IntoIterator for &'a SudokuGrid {
+    type Item = Cell;
+    type IntoIter = std::iter::Copied<std::slice::Iter<'a, Cell>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.cells.as_flattened().iter().copied()
+    }
+}
+
 impl FromStr for SudokuGrid {
-    type Err = SudokuError;
+    type Err = SudokuParseError;
 
+    /// Parses 81 characters in row-major order: `1`-`9` for a guess and `.`, `0` or `_` for
+    /// an empty cell. Every character is checked first, so malformed input never panics.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.len() != 81 {
-            return Err(SudokuError::InvalidInputLength(s.len()));
+        let chars: Vec<char> = s.chars().collect();
+        if chars.len() != 81 {
+            return Err(SudokuParseError::WrongLength { got: chars.len() });
+        }
+        if let Some((index, &ch)) = chars
+            .iter()
+            .enumerate()
+            .find(|(_, ch)| !matches!(ch, '1'..='9' | '.' | '0' | '_'))
+        {
+            return Err(SudokuParseError::InvalidChar { index, ch });
         }
         let mut cells = [[Cell::Empty; 9]; 9];
-        for (i, c) in s.chars().enumerate() {
+        for (i, c) in chars.into_iter().enumerate() {
             cells[i / 9][i % 9] = Cell::guess_from_char(c);
         }
         Ok(Self { cells })
@@ -92,15 +321,70 @@ impl fmt::Display for SudokuGrid {
     }
 }
 
-#[derive(Debug, thiserror::Error)]
-pub enum SudokuError {
-    #[error("Invalid input length: {0}, expected 81 characters")]
-    InvalidInputLength(usize),
+/// Serialized as a pair of 81-character `from_str` lines, the hints and then every cell, read
+/// back through [`SudokuGrid::from_puzzle_and_solution`]. A single line can't mark hints, and
+/// only hints are wired to the graph's clique nodes, so both are needed to keep the puzzle.
+#[cfg(feature = "serde")]
+impl serde::Serialize for SudokuGrid {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let line = |grid: &SudokuGrid| -> String {
+            grid.into_iter().map(|cell| cell.to_string()).collect()
+        };
+        serde::Serialize::serialize(&(line(&self.hints()), line(self)), serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for SudokuGrid {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (hints, cells) = <(String, String)>::deserialize(deserializer)?;
+        Self::from_puzzle_and_solution(&hints, &cells).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Result of [`SudokuGrid::validate`], listing every conflict when the grid is invalid
+pub type GridValidation = Result<(), Vec<Conflict>>;
+
+/// A value appearing more than once in a single unit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Conflict {
+    pub unit: UnitKind,
+    pub position: Position,
+    pub value: Value,
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} repeats {}",
+            self.unit,
+            self.position.to_index() + 1,
+            self.value
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum SudokuParseError {
+    #[error("Invalid input length: {got}, expected 81 characters")]
+    WrongLength { got: usize },
+    #[error("Invalid character {ch:?} at index {index}, expected 1-9, '.', '0' or '_'")]
+    InvalidChar { index: usize, ch: char },
+    #[error("Solution disagrees with the puzzle's clue at index {index}")]
+    SolutionMismatch { index: usize },
+    #[error("Clue index {0} is out of range, expected 0-80")]
+    IndexOutOfRange(usize),
+    #[error("Clue at index {index} has digit {digit}, expected 1-9")]
+    InvalidDigit { index: usize, digit: u8 },
 }
 
 #[cfg(test)]
 mod test {
+    use std::collections::HashMap;
+
     use super::*;
+    use crate::Value;
 
     const INPUT: &str = include_str!("../../data/validation.csv");
 
@@ -120,4 +404,446 @@ mod test {
             .collect::<Vec<_>>();
         assert_eq!(grids.len(), len);
     }
+
+    #[test]
+    fn test_get_set_cell_round_trip() {
+        let mut grid = SudokuGrid::new();
+        for (i, point) in Position::all_board_positions().enumerate() {
+            let value = Value::from_index(i % 9);
+            let cell = if i % 2 == 0 {
+                Cell::Hint(value)
+            } else {
+                Cell::Guess(value)
+            };
+            assert_eq!(grid.get_cell(point), Cell::Empty);
+            grid.set_cell(point, cell);
+            assert_eq!(grid.get_cell(point), cell);
+        }
+        // Each write landed in its own cell
+        for (i, point) in Position::all_board_positions().enumerate() {
+            assert_eq!(grid.get_cell(point).value(), Some(Value::from_index(i % 9)));
+            assert_eq!(grid.get_cell(point).is_hint(), i % 2 == 0);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let line = INPUT.lines().next().unwrap().trim();
+        let mut puzzle: String = line.chars().take(80).collect();
+        puzzle.push('.');
+        let grid = SudokuGrid::from_str(&puzzle).unwrap();
+
+        let json = serde_json::to_string(&grid).unwrap();
+        assert_eq!(json, format!("[\"{}\",\"{puzzle}\"]", ".".repeat(81)));
+        assert_eq!(serde_json::from_str::<SudokuGrid>(&json).unwrap(), grid);
+        assert!(serde_json::from_str::<SudokuGrid>("[\"123\",\"123\"]").is_err());
+        assert!(serde_json::from_str::<SudokuGrid>(&format!("\"{puzzle}\"")).is_err());
+
+        // Hints come back as hints, so the rebuilt graph is anchored the same way
+        let hints: String = line
+            .chars()
+            .enumerate()
+            .map(|(i, c)| if i % 2 == 0 { c } else { '.' })
+            .collect();
+        let puzzle = SudokuGrid::from_puzzle_and_solution(&hints, line).unwrap();
+        let json = serde_json::to_string(&puzzle).unwrap();
+        let decoded = serde_json::from_str::<SudokuGrid>(&json).unwrap();
+        assert_eq!(decoded, puzzle);
+        let graph = crate::Graph::from_sudoku(&decoded);
+        assert!(graph.structurally_eq(&crate::Graph::from_sudoku(&puzzle)));
+        let public = crate::Graph::from_puzzle(&decoded);
+        assert!(public.structurally_eq(&crate::Graph::from_puzzle(&puzzle)));
+
+        // Cells keep whether they're hints
+        for cell in [
+            Cell::Empty,
+            Cell::Guess(Value::Four),
+            Cell::Hint(Value::Four),
+        ] {
+            let json = serde_json::to_string(&cell).unwrap();
+            assert_eq!(serde_json::from_str::<Cell>(&json).unwrap(), cell);
+        }
+        assert_ne!(
+            serde_json::to_string(&Cell::Guess(Value::Four)).unwrap(),
+            serde_json::to_string(&Cell::Hint(Value::Four)).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let line = INPUT.lines().next().unwrap().trim();
+        assert_eq!(
+            SudokuGrid::from_str(&line[..80]),
+            Err(SudokuParseError::WrongLength { got: 80 })
+        );
+        assert_eq!(
+            SudokuGrid::from_str(""),
+            Err(SudokuParseError::WrongLength { got: 0 })
+        );
+
+        let mut chars: Vec<char> = line.chars().collect();
+        chars[40] = 'x';
+        let bad: String = chars.iter().collect();
+        assert_eq!(
+            SudokuGrid::from_str(&bad),
+            Err(SudokuParseError::InvalidChar { index: 40, ch: 'x' })
+        );
+
+        // Multi-byte characters are counted as one cell each rather than panicking
+        chars[40] = 'é';
+        let accented: String = chars.iter().collect();
+        assert_eq!(
+            SudokuGrid::from_str(&accented),
+            Err(SudokuParseError::InvalidChar {
+                index: 40, ch: 'é'
+            })
+        );
+
+        let blanks = format!("0_{}", ".".repeat(79));
+        assert_eq!(SudokuGrid::from_str(&blanks).unwrap(), SudokuGrid::new());
+    }
+
+    #[test]
+    fn test_from_puzzle_and_solution() {
+        let solution = INPUT.lines().next().unwrap().trim();
+        let puzzle = SudokuGrid::from_str(solution)
+            .unwrap()
+            .keep_every(3)
+            .into_iter()
+            .join("");
+
+        let grid = SudokuGrid::from_puzzle_and_solution(&puzzle, solution).unwrap();
+        for (i, point) in Position::all_board_positions().enumerate() {
+            let cell = grid.get_cell(point);
+            assert_eq!(cell.is_hint(), i % 3 == 0);
+            assert!(cell.is_filled());
+        }
+        assert_eq!(grid.hints().clue_count(), 27);
+        assert!(grid.hints_match(&SudokuGrid::from_str(&puzzle).unwrap()));
+        // The hints are what anchors the graph to the clique nodes
+        assert_eq!(crate::Graph::from_sudoku(&grid).node_count(), 90);
+
+        // A clue the solution contradicts
+        let mut wrong: Vec<char> = puzzle.chars().collect();
+        wrong[3] = if wrong[3] == '1' { '2' } else { '1' };
+        let wrong: String = wrong.into_iter().collect();
+        assert_eq!(
+            SudokuGrid::from_puzzle_and_solution(&wrong, solution),
+            Err(SudokuParseError::SolutionMismatch { index: 3 })
+        );
+        assert_eq!(
+            SudokuGrid::from_puzzle_and_solution(&puzzle, "123"),
+            Err(SudokuParseError::WrongLength { got: 3 })
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        let line = INPUT.lines().next().unwrap();
+        let mut grid = SudokuGrid::from_str(line.trim()).unwrap();
+        assert_eq!(grid.validate(), Ok(()));
+
+        // Copy the second cell's value into the first, breaking row one, column one and box one
+        let second = Point::new(Position::ONE, Position::TWO);
+        let value = grid.get_cell(second).value().unwrap();
+        grid.set_cell(Point::new(Position::ONE, Position::ONE), Cell::Guess(value));
+        let conflicts = grid.validate().unwrap_err();
+        assert_eq!(conflicts.len(), 3);
+        assert_eq!(
+            conflicts[0],
+            Conflict {
+                unit: UnitKind::Row,
+                position: Position::ONE,
+                value
+            }
+        );
+        assert_eq!(conflicts[0].to_string(), format!("row 1 repeats {value}"));
+        assert_eq!(conflicts[2].unit, UnitKind::Box);
+        assert!(!grid.is_valid_solution());
+    }
+
+    #[test]
+    fn test_from_clue_map() {
+        let clues = HashMap::from([(0, 5), (10, 3), (80, 9)]);
+        let mut expected = String::from("5.........3");
+        expected.push_str(&".".repeat(69));
+        expected.push('9');
+        assert_eq!(
+            SudokuGrid::from_clue_map(&clues).unwrap(),
+            SudokuGrid::from_str(&expected).unwrap()
+        );
+        assert_eq!(
+            SudokuGrid::from_clue_map(&HashMap::new()).unwrap(),
+            SudokuGrid::new()
+        );
+
+        assert_eq!(
+            SudokuGrid::from_clue_map(&HashMap::from([(81, 1)])),
+            Err(SudokuParseError::IndexOutOfRange(81))
+        );
+        for digit in [0, 10] {
+            assert_eq!(
+                SudokuGrid::from_clue_map(&HashMap::from([(4, digit)])),
+                Err(SudokuParseError::InvalidDigit { index: 4, digit })
+            );
+        }
+    }
+
+    #[test]
+    fn test_diff() {
+        let line = INPUT.lines().next().unwrap();
+        let grid = SudokuGrid::from_str(line).unwrap();
+        assert!(grid.diff(&grid).is_empty());
+
+        let mut changed = grid;
+        let point = Point::new(Position::THREE, Position::SEVEN);
+        changed.set_cell(point, Cell::Empty);
+
+        let diff = grid.diff(&changed);
+        assert_eq!(diff, vec![(point, grid.get_cell(point), Cell::Empty)]);
+    }
+
+    #[test]
+    fn test_get_set() {
+        let line = INPUT.lines().next().unwrap();
+        let grid = SudokuGrid::from_str(line).unwrap();
+
+        for pos in Position::ALL_POSITIONS {
+            let row = grid.get_set::<Row>(pos);
+            let column = grid.get_set::<Column>(pos);
+            let square = grid.get_set::<Box>(pos);
+
+            assert_eq!(row.cells(), grid.get_row(pos).cells());
+            assert_eq!(column.cells(), grid.get_column(pos).cells());
+            assert_eq!(square.cells(), grid.get_square(pos).cells());
+            assert_eq!(row.position(), pos);
+            assert_eq!(column.position(), pos);
+            assert_eq!(square.position(), pos);
+        }
+    }
+
+    #[test]
+    fn test_grid_as_hash_key() {
+        let mut lines = INPUT.lines();
+        let first = SudokuGrid::from_str(lines.next().unwrap()).unwrap();
+        let second = SudokuGrid::from_str(lines.next().unwrap()).unwrap();
+
+        let mut cache = HashMap::new();
+        cache.insert(first, "first");
+        cache.insert(second, "second");
+        assert_eq!(cache.len(), 2);
+
+        let lookup = SudokuGrid::from_str(INPUT.lines().next().unwrap()).unwrap();
+        assert_eq!(cache.get(&lookup), Some(&"first"));
+
+        // Turning a guess into a hint of the same value gives a different key
+        let mut hinted = lookup;
+        let point = Point::default();
+        hinted.set_cell(point, Cell::Hint(lookup.get_cell(point).value().unwrap()));
+        assert_eq!(cache.get(&hinted), None);
+    }
+
+    #[test]
+    fn test_units() {
+        let line = INPUT.lines().next().unwrap();
+        let grid = SudokuGrid::from_str(line).unwrap();
+
+        let units: Vec<Unit> = grid.units().collect();
+        assert_eq!(units.len(), 27);
+        assert!(
+            units
+                .iter()
+                .all(|unit| unit.is_valid() && unit.is_complete())
+        );
+        assert_eq!(
+            units
+                .iter()
+                .filter(|unit| matches!(unit, Unit::Box(_)))
+                .count(),
+            9
+        );
+    }
+
+    #[test]
+    fn test_completion_ratio() {
+        let line = INPUT.lines().next().unwrap();
+        let solution = SudokuGrid::from_str(line).unwrap();
+        assert!(solution.is_full());
+        assert_eq!(solution.completion_ratio(), 1.0);
+
+        // 30 hints plus 10 guesses
+        let mut grid = SudokuGrid::new();
+        for (i, point) in Position::all_board_positions().take(40).enumerate() {
+            let value = solution.get_cell(point).value().unwrap();
+            let cell = if i < 30 {
+                Cell::Hint(value)
+            } else {
+                Cell::Guess(value)
+            };
+            grid.set_cell(point, cell);
+        }
+        assert!(!grid.is_full());
+        assert_eq!(grid.completion_ratio(), 40.0 / 81.0);
+        assert_eq!(SudokuGrid::new().completion_ratio(), 0.0);
+    }
+
+    #[test]
+    fn test_hints_match() {
+        let line = INPUT.lines().next().unwrap();
+        let solution = SudokuGrid::from_str(line).unwrap();
+
+        let mut puzzle = SudokuGrid::new();
+        for point in Position::all_board_positions().step_by(3) {
+            puzzle.set_cell(point, Cell::Hint(solution.get_cell(point).value().unwrap()));
+        }
+        assert!(puzzle.hints_match(&solution));
+        assert!(SudokuGrid::new().hints_match(&solution));
+
+        // One wrong hint is enough to fail
+        let point = Point::default();
+        let value = solution.get_cell(point).value().unwrap();
+        let wrong = Value::from_index((value.to_index() + 1) % 9);
+        puzzle.set_cell(point, Cell::Hint(wrong));
+        assert!(!puzzle.hints_match(&solution));
+
+        // A hint over an empty solution cell doesn't match either
+        puzzle.set_cell(point, Cell::Hint(value));
+        let mut partial = solution;
+        partial.set_cell(point, Cell::Empty);
+        assert!(!puzzle.hints_match(&partial));
+    }
+
+    #[test]
+    fn test_keep_every() {
+        let solution = SudokuGrid::test_solution();
+        let puzzle = solution.keep_every(3);
+        for (i, point) in Position::all_board_positions().enumerate() {
+            let expected = if i % 3 == 0 {
+                solution.get_cell(point)
+            } else {
+                Cell::Empty
+            };
+            assert_eq!(puzzle.get_cell(point), expected);
+        }
+        assert_eq!(puzzle.clue_count(), 27);
+        assert_eq!(solution.keep_every(1), solution);
+        assert_eq!(solution.keep_every(81).clue_count(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "keep_every needs a step of at least 1")]
+    fn test_keep_every_zero() {
+        SudokuGrid::test_solution().keep_every(0);
+    }
+
+    #[test]
+    fn test_into_iterator() {
+        let line = INPUT.lines().next().unwrap();
+        let mut grid = SudokuGrid::from_str(line).unwrap();
+        grid.set_cell(Point::default(), Cell::Empty);
+
+        let mut filled = 0;
+        for cell in &grid {
+            if cell.is_filled() {
+                filled += 1;
+            }
+        }
+        assert_eq!(filled, 80);
+        assert_eq!(filled, grid.clue_count());
+
+        // Row-major, matching the board positions
+        let cells: Vec<Cell> = (&grid).into_iter().collect();
+        let expected: Vec<Cell> = Position::all_board_positions()
+            .map(|point| grid.get_cell(point))
+            .collect();
+        assert_eq!(cells, expected);
+    }
+
+    #[test]
+    fn test_clear_guesses() {
+        let line = INPUT.lines().next().unwrap();
+        let solution = SudokuGrid::from_str(line).unwrap();
+
+        // Alternate hints and guesses over the first two rows, leaving the rest empty
+        let mut grid = SudokuGrid::new();
+        for (i, point) in Position::all_board_positions().take(18).enumerate() {
+            let value = solution.get_cell(point).value().unwrap();
+            let cell = if i % 2 == 0 {
+                Cell::Hint(value)
+            } else {
+                Cell::Guess(value)
+            };
+            grid.set_cell(point, cell);
+        }
+
+        let mut cleared = grid;
+        cleared.clear_guesses();
+        assert_eq!(cleared.clue_count(), 9);
+        for point in Position::all_board_positions() {
+            let before = grid.get_cell(point);
+            let after = cleared.get_cell(point);
+            if before.is_guess() {
+                assert!(after.is_empty());
+            } else {
+                assert_eq!(after, before);
+            }
+        }
+    }
+
+    #[test]
+    fn test_relabel_keeps_solutions_valid() {
+        for line in INPUT.lines().take(5) {
+            let grid = SudokuGrid::from_str(line).unwrap();
+            assert!(grid.is_valid_solution());
+            for _ in 0..100 {
+                let shuffle = ColourShuffle::new_random();
+                let relabelled = grid.relabel(&shuffle);
+                assert!(relabelled.is_valid_solution());
+                assert!(relabelled.is_full());
+            }
+        }
+    }
+
+    #[test]
+    fn test_transforms_in_place() {
+        let line = INPUT.lines().next().unwrap();
+        let grid = SudokuGrid::from_str(line).unwrap();
+
+        let mut transposed = grid;
+        transposed.transpose_in_place();
+        assert_eq!(transposed, grid.transpose());
+        assert!(transposed.is_valid_solution());
+        let corner = Point::new(Position::ONE, Position::NINE);
+        assert_eq!(
+            transposed.get_cell(corner),
+            grid.get_cell(Point::new(corner.y(), corner.x()))
+        );
+
+        let mut rotated = grid;
+        rotated.rotate90_in_place();
+        assert_eq!(rotated, grid.rotate90());
+        assert!(rotated.is_valid_solution());
+        // The first column, read bottom to top, becomes the first row
+        let first_column: Vec<_> = grid.get_column(Position::ONE).cells().to_vec();
+        let first_row: Vec<_> = rotated.get_row(Position::ONE).cells().to_vec();
+        assert_eq!(
+            first_row,
+            first_column.into_iter().rev().collect::<Vec<_>>()
+        );
+        assert_eq!(grid.rotate90().rotate90().rotate90().rotate90(), grid);
+
+        let shuffle = ColourShuffle::new_random();
+        let mut relabelled = grid;
+        relabelled.relabel_in_place(&shuffle);
+        assert_eq!(relabelled, grid.relabel(&shuffle));
+        assert!(relabelled.is_valid_solution());
+        for point in Position::all_board_positions() {
+            let value = grid.get_cell(point).value().unwrap();
+            assert_eq!(
+                relabelled.get_cell(point).value(),
+                Some(shuffle.apply(value))
+            );
+        }
+    }
 }
@@ -0,0 +1,153 @@
+use std::{collections::HashSet, ops::RangeBounds};
+
+use rand::{Rng, seq::IndexedRandom};
+
+use super::{Position, SudokuGrid, Value};
+
+/// A collection of puzzles that can be narrowed down and sampled from.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PuzzleLibrary {
+    puzzles: Vec<SudokuGrid>,
+}
+
+impl PuzzleLibrary {
+    pub fn new(puzzles: Vec<SudokuGrid>) -> Self {
+        Self { puzzles }
+    }
+
+    pub fn puzzles(&self) -> &[SudokuGrid] {
+        &self.puzzles
+    }
+
+    pub fn len(&self) -> usize {
+        self.puzzles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.puzzles.is_empty()
+    }
+
+    /// Keeps the puzzles whose clue count falls within `range`
+    pub fn filter_by_clue_count(&self, range: impl RangeBounds<usize>) -> Self {
+        self.puzzles
+            .iter()
+            .filter(|puzzle| range.contains(&puzzle.clue_count()))
+            .copied()
+            .collect()
+    }
+
+    /// Picks a puzzle uniformly at random, or `None` if the library is empty
+    pub fn random<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<&SudokuGrid> {
+        self.puzzles.choose(rng)
+    }
+
+    /// Drops puzzles that are a digit relabelling of one earlier in the library,
+    /// keeping the first occurrence.
+    pub fn dedup_by_canonical(&mut self) {
+        let mut seen = HashSet::new();
+        self.puzzles
+            .retain(|puzzle| seen.insert(puzzle.canonical_relabelling()));
+    }
+}
+
+impl FromIterator<SudokuGrid> for PuzzleLibrary {
+    fn from_iter<I: IntoIterator<Item = SudokuGrid>>(iter: I) -> Self {
+        Self::new(iter.into_iter().collect())
+    }
+}
+
+impl SudokuGrid {
+    /// Relabels the digits so they first appear in ascending order when read row by row.
+    /// Two grids that only differ by a permutation of the digits share the same canonical form.
+    pub fn canonical_relabelling(&self) -> Self {
+        let mut mapping: [Option<Value>; 9] = [None; 9];
+        let mut next = 0;
+        for point in Position::all_board_positions() {
+            if let Some(value) = self.get_cell(point).value() {
+                let slot = &mut mapping[value.to_index()];
+                if slot.is_none() {
+                    *slot = Some(Value::from_index(next));
+                    next += 1;
+                }
+            }
+        }
+
+        let mut canonical = *self;
+        for point in Position::all_board_positions() {
+            let cell = self.get_cell(point);
+            // Every filled value was assigned a label in the pass above
+            canonical.set_cell(
+                point,
+                cell.map_value(|value| mapping[value.to_index()].unwrap_or(value)),
+            );
+        }
+        canonical
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use super::*;
+
+    /// Swaps every 1 with a 2 and vice versa.
+    fn swap_ones_and_twos(grid: SudokuGrid) -> SudokuGrid {
+        let mut swapped = grid;
+        for point in Position::all_board_positions() {
+            let cell = grid.get_cell(point).map_value(|value| match value {
+                Value::One => Value::Two,
+                Value::Two => Value::One,
+                value => value,
+            });
+            swapped.set_cell(point, cell);
+        }
+        swapped
+    }
+
+    fn library() -> PuzzleLibrary {
+        [
+            SudokuGrid::test_solution(),
+            SudokuGrid::test_solution().keep_every(2),
+            SudokuGrid::test_solution().keep_every(3),
+            swap_ones_and_twos(SudokuGrid::test_solution()),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    #[test]
+    fn test_filter_by_clue_count() {
+        let library = library();
+        assert_eq!(library.filter_by_clue_count(81..).len(), 2);
+        assert_eq!(library.filter_by_clue_count(..=27).len(), 1);
+        assert_eq!(library.filter_by_clue_count(20..50).len(), 2);
+        assert!(library.filter_by_clue_count(82..).is_empty());
+    }
+
+    #[test]
+    fn test_random() {
+        let library = library();
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..20 {
+            let puzzle = library.random(&mut rng).unwrap();
+            assert!(library.puzzles().contains(puzzle));
+        }
+        assert!(PuzzleLibrary::default().random(&mut rng).is_none());
+    }
+
+    #[test]
+    fn test_dedup_by_canonical() {
+        let mut library = library();
+        library.dedup_by_canonical();
+
+        // The relabelled copy of the solution is dropped, the first occurrence kept
+        assert_eq!(library.len(), 3);
+        assert_eq!(library.puzzles()[0], SudokuGrid::test_solution());
+        assert!(
+            !library
+                .puzzles()
+                .contains(&swap_ones_and_twos(SudokuGrid::test_solution()))
+        );
+    }
+}
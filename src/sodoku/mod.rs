@@ -1,13 +1,19 @@
+mod bitset;
 mod cell;
 mod grid;
+mod library;
 mod point;
 mod position;
 mod set;
+mod solver;
 mod value;
 
+pub use bitset::*;
 pub use cell::*;
 pub use grid::*;
+pub use library::*;
 pub use point::*;
 pub use position::*;
 pub use set::*;
+pub use solver::*;
 pub use value::*;
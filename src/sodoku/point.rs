@@ -20,6 +20,60 @@ impl Point {
     pub fn y(&self) -> Position {
         self.y
     }
+
+    /// Returns the 20 points sharing a row, column or box with this point, excluding itself
+    pub fn peers(&self) -> Vec<Point> {
+        let (x, y) = (self.x.to_index(), self.y.to_index());
+        Position::all_board_positions()
+            .filter(|other| {
+                let (ox, oy) = (other.x.to_index(), other.y.to_index());
+                let same_box = ox / 3 == x / 3 && oy / 3 == y / 3;
+                other != self && (ox == x || oy == y || same_box)
+            })
+            .collect()
+    }
+
+    /// Returns the in-bounds points a chess king could move to from this point.
+    /// Used by "anti-king" variants where these cells must all differ.
+    pub fn king_neighbors(&self) -> Vec<Point> {
+        self.offset_neighbors(&[
+            (-1, -1),
+            (-1, 0),
+            (-1, 1),
+            (0, -1),
+            (0, 1),
+            (1, -1),
+            (1, 0),
+            (1, 1),
+        ])
+    }
+
+    /// Returns the in-bounds points a chess knight could move to from this point.
+    /// Used by "anti-knight" variants where these cells must all differ.
+    pub fn knight_neighbors(&self) -> Vec<Point> {
+        self.offset_neighbors(&[
+            (-2, -1),
+            (-2, 1),
+            (-1, -2),
+            (-1, 2),
+            (1, -2),
+            (1, 2),
+            (2, -1),
+            (2, 1),
+        ])
+    }
+
+    fn offset_neighbors(&self, offsets: &[(isize, isize)]) -> Vec<Point> {
+        offsets
+            .iter()
+            .filter_map(|&(dx, dy)| {
+                let x = self.x.to_index().checked_add_signed(dx)?;
+                let y = self.y.to_index().checked_add_signed(dy)?;
+                (x < 9 && y < 9)
+                    .then(|| Point::new(Position::from_index(x), Position::from_index(y)))
+            })
+            .collect()
+    }
 }
 
 impl Index<Point> for [[Cell; 9]; 9] {
@@ -34,3 +88,54 @@ impl IndexMut<Point> for [[Cell; 9]; 9] {
         &mut self[index.x][index.y]
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_king_neighbors() {
+        let center = Point::new(Position::FIVE, Position::FIVE);
+        let edge = Point::new(Position::ONE, Position::FIVE);
+        let corner = Point::new(Position::NINE, Position::NINE);
+
+        assert_eq!(center.king_neighbors().len(), 8);
+        assert_eq!(edge.king_neighbors().len(), 5);
+        assert_eq!(corner.king_neighbors().len(), 3);
+
+        for neighbor in center.king_neighbors() {
+            let dx = neighbor.x().to_index().abs_diff(center.x().to_index());
+            let dy = neighbor.y().to_index().abs_diff(center.y().to_index());
+            assert!(dx <= 1 && dy <= 1 && neighbor != center);
+        }
+    }
+
+    #[test]
+    fn test_peers() {
+        let point = Point::new(Position::FOUR, Position::SEVEN);
+        let peers = point.peers();
+        assert_eq!(peers.len(), 20);
+        assert!(!peers.contains(&point));
+        assert!(peers.contains(&Point::new(Position::FOUR, Position::ONE)));
+        assert!(peers.contains(&Point::new(Position::NINE, Position::SEVEN)));
+        assert!(peers.contains(&Point::new(Position::SIX, Position::NINE)));
+        assert!(!peers.contains(&Point::new(Position::SEVEN, Position::NINE)));
+    }
+
+    #[test]
+    fn test_knight_neighbors() {
+        let center = Point::new(Position::FIVE, Position::FIVE);
+        let edge = Point::new(Position::ONE, Position::FIVE);
+        let corner = Point::new(Position::ONE, Position::ONE);
+
+        assert_eq!(center.knight_neighbors().len(), 8);
+        assert_eq!(edge.knight_neighbors().len(), 4);
+        assert_eq!(corner.knight_neighbors().len(), 2);
+
+        for neighbor in center.knight_neighbors() {
+            let dx = neighbor.x().to_index().abs_diff(center.x().to_index());
+            let dy = neighbor.y().to_index().abs_diff(center.y().to_index());
+            assert!((dx, dy) == (1, 2) || (dx, dy) == (2, 1));
+        }
+    }
+}
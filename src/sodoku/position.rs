@@ -73,6 +73,38 @@ impl Position {
         }
     }
 
+    /// The following position, or `None` after `NINE`
+    pub fn next(self) -> Option<Position> {
+        let index = self.to_index() + 1;
+        (index < 9).then(|| Position::from_index(index))
+    }
+
+    /// The preceding position, or `None` before `ONE`
+    pub fn prev(self) -> Option<Position> {
+        self.to_index().checked_sub(1).map(Position::from_index)
+    }
+
+    /// The following position, wrapping from `NINE` back round to `ONE`
+    pub fn succ_wrapping(self) -> Position {
+        Position::from_index((self.to_index() + 1) % 9)
+    }
+
+    /// The three row positions in this row's band, e.g. `FOUR`, `FIVE` and `SIX` for `FIVE`
+    pub fn band(&self) -> [Position; 3] {
+        self.third()
+    }
+
+    /// The three column positions in this column's stack, mirroring [`Position::band`]
+    pub fn stack(&self) -> [Position; 3] {
+        self.third()
+    }
+
+    // The run of three positions this one falls in
+    fn third(&self) -> [Position; 3] {
+        let start = self.to_index() / 3 * 3;
+        [0, 1, 2].map(|offset| Position::from_index(start + offset))
+    }
+
     /// Returns an iterator over all the positions on the board.
     pub fn all_board_positions() -> impl Iterator<Item = Point> {
         itertools::iproduct!(Self::ALL_POSITIONS, Self::ALL_POSITIONS)
@@ -281,6 +313,36 @@ mod test {
         assert!(Position::try_from(10u8).is_err());
     }
 
+    #[test]
+    fn test_next_prev() {
+        assert_eq!(Position::ONE.next(), Some(Position::TWO));
+        assert_eq!(Position::NINE.next(), None);
+        assert_eq!(Position::NINE.prev(), Some(Position::EIGHT));
+        assert_eq!(Position::ONE.prev(), None);
+
+        assert_eq!(Position::FOUR.succ_wrapping(), Position::FIVE);
+        assert_eq!(Position::NINE.succ_wrapping(), Position::ONE);
+
+        // Stepping with next visits every position in order
+        let walked: Vec<_> = std::iter::successors(Some(Position::ONE), |p| p.next()).collect();
+        assert_eq!(walked, Position::ALL_POSITIONS);
+    }
+
+    #[test]
+    fn test_band_and_stack() {
+        use Position::*;
+        assert_eq!(FIVE.band(), [FOUR, FIVE, SIX]);
+        assert_eq!(FIVE.stack(), [FOUR, FIVE, SIX]);
+        assert_eq!(ONE.band(), [ONE, TWO, THREE]);
+        assert_eq!(NINE.stack(), [SEVEN, EIGHT, NINE]);
+        for pos in Position::ALL_POSITIONS {
+            assert!(pos.band().contains(&pos));
+            for other in pos.band() {
+                assert_eq!(other.band(), pos.band());
+            }
+        }
+    }
+
     #[test]
     fn test_all_positions_constant() {
         assert_eq!(Position::ALL_POSITIONS.len(), 9);
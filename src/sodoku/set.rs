@@ -1,8 +1,8 @@
-use std::marker::PhantomData;
+use std::{fmt, marker::PhantomData};
 
 use itertools::Itertools;
 
-use super::{cell::Cell, position::Position};
+use super::{SudokuGrid, Value, cell::Cell, position::Position};
 
 pub struct Row;
 pub struct Column;
@@ -14,19 +14,37 @@ pub trait SetType {
             .last()
             .unwrap_or("Unknown")
     }
+
+    /// Extracts this kind of unit at `pos` from a grid
+    fn get_set(grid: &SudokuGrid, pos: Position) -> Set<Self>
+    where
+        Self: Sized;
+}
+impl SetType for Row {
+    fn get_set(grid: &SudokuGrid, pos: Position) -> Set<Self> {
+        grid.get_row(pos)
+    }
+}
+impl SetType for Column {
+    fn get_set(grid: &SudokuGrid, pos: Position) -> Set<Self> {
+        grid.get_column(pos)
+    }
+}
+impl SetType for Box {
+    fn get_set(grid: &SudokuGrid, pos: Position) -> Set<Self> {
+        grid.get_square(pos)
+    }
 }
-impl SetType for Row {}
-impl SetType for Column {}
-impl SetType for Box {}
 
-pub struct Set<T: SetType> {
-    cells: [Cell; 9],
+/// One unit of `N` cells, 9 for a standard grid. A unit of size `N` holds the values 1 to `N`.
+pub struct Set<T: SetType, const N: usize = 9> {
+    cells: [Cell; N],
     position: Position,
     set_type: PhantomData<T>,
 }
 
-impl<T: SetType> Set<T> {
-    pub fn new(cells: [Cell; 9], position: Position) -> Self {
+impl<T: SetType, const N: usize> Set<T, N> {
+    pub fn new(cells: [Cell; N], position: Position) -> Self {
         Self {
             cells,
             position,
@@ -34,7 +52,7 @@ impl<T: SetType> Set<T> {
         }
     }
 
-    pub fn cells(&self) -> &[Cell; 9] {
+    pub fn cells(&self) -> &[Cell; N] {
         &self.cells
     }
 
@@ -42,6 +60,11 @@ impl<T: SetType> Set<T> {
         self.position
     }
 
+    /// Each cell's value, with `None` for an empty cell, for numeric work on the unit
+    pub fn values(&self) -> [Option<Value>; N] {
+        self.cells.map(|cell| cell.value())
+    }
+
     pub fn is_complete(&self) -> bool {
         if !self.is_filled() {
             return false;
@@ -50,22 +73,25 @@ impl<T: SetType> Set<T> {
         // Get the values as a vec and check they're all unique
         let values: Vec<_> = self.cells.iter().filter_map(|cell| cell.value()).collect();
 
-        if values.len() != 9 {
+        if values.len() != N {
             return false;
         }
 
-        // Check that all values are unique
-        values.iter().all_unique()
+        // Check that all values are unique and fit the set's size
+        values.iter().all(|value| Self::in_range(*value)) && values.iter().all_unique()
     }
 
-    /// Checks if the set is valid so far - no duplicate values
+    /// Checks if the set is valid so far - no duplicate or out-of-range values
     /// (but may contain empties or be incomplete)
     pub fn is_valid(&self) -> bool {
         // Only check non-empty cells for uniqueness
-        self.cells
-            .iter()
-            .filter_map(|cell| cell.value())
-            .all_unique()
+        let mut values = self.cells.iter().filter_map(|cell| cell.value());
+        values.clone().all(Self::in_range) && values.all_unique()
+    }
+
+    /// Whether `value` can appear in a set of `N` cells
+    fn in_range(value: Value) -> bool {
+        usize::from(value.to_numeric()) <= N
     }
 
     /// Checks if all cells are empty
@@ -77,6 +103,88 @@ impl<T: SetType> Set<T> {
     pub fn is_filled(&self) -> bool {
         self.cells.iter().all(|cell| cell.is_filled())
     }
+
+    /// Index of the first empty cell in the set, if any
+    pub fn first_empty(&self) -> Option<usize> {
+        self.cells.iter().position(|cell| cell.is_empty())
+    }
+
+    /// Indices of every filled cell in the set
+    pub fn filled_positions(&self) -> Vec<usize> {
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(|(_, cell)| cell.is_filled())
+            .map(|(i, _)| i)
+            .collect()
+    }
+}
+
+/// Any one of the 27 units of a grid, for code that treats rows, columns and boxes alike
+pub enum Unit {
+    Row(Set<Row>),
+    Column(Set<Column>),
+    Box(Set<Box>),
+}
+
+/// Which kind of unit a [`Unit`] is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UnitKind {
+    Row,
+    Column,
+    Box,
+}
+
+impl fmt::Display for UnitKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UnitKind::Row => write!(f, "row"),
+            UnitKind::Column => write!(f, "column"),
+            UnitKind::Box => write!(f, "box"),
+        }
+    }
+}
+
+impl Unit {
+    pub fn kind(&self) -> UnitKind {
+        match self {
+            Unit::Row(_) => UnitKind::Row,
+            Unit::Column(_) => UnitKind::Column,
+            Unit::Box(_) => UnitKind::Box,
+        }
+    }
+
+    pub fn cells(&self) -> &[Cell; 9] {
+        match self {
+            Unit::Row(set) => set.cells(),
+            Unit::Column(set) => set.cells(),
+            Unit::Box(set) => set.cells(),
+        }
+    }
+
+    pub fn position(&self) -> Position {
+        match self {
+            Unit::Row(set) => set.position(),
+            Unit::Column(set) => set.position(),
+            Unit::Box(set) => set.position(),
+        }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        match self {
+            Unit::Row(set) => set.is_valid(),
+            Unit::Column(set) => set.is_valid(),
+            Unit::Box(set) => set.is_valid(),
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        match self {
+            Unit::Row(set) => set.is_complete(),
+            Unit::Column(set) => set.is_complete(),
+            Unit::Box(set) => set.is_complete(),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -186,6 +294,32 @@ mod test {
         assert!(set.is_valid());
     }
 
+    #[test]
+    fn test_empty_and_filled_positions() {
+        let cells = [
+            Cell::new_hint(1),
+            Cell::new_guess(2),
+            Cell::new_empty(),
+            Cell::new_guess(4),
+            Cell::new_empty(),
+            Cell::new_empty(),
+            Cell::new_hint(7),
+            Cell::new_empty(),
+            Cell::new_guess(9),
+        ];
+        let set: Set<Box> = Set::new(cells, Position::TWO);
+        assert_eq!(set.first_empty(), Some(2));
+        assert_eq!(set.filled_positions(), vec![0, 1, 3, 6, 8]);
+
+        let full: Set<Row> = Set::new([Cell::new_guess(1); 9], Position::ONE);
+        assert_eq!(full.first_empty(), None);
+        assert_eq!(full.filled_positions(), (0..9).collect::<Vec<_>>());
+
+        let empty: Set<Column> = Set::new([Cell::new_empty(); 9], Position::ONE);
+        assert_eq!(empty.first_empty(), Some(0));
+        assert!(empty.filled_positions().is_empty());
+    }
+
     #[test]
     fn test_getters() {
         let cells = [Cell::new_guess(1); 9];
@@ -196,6 +330,61 @@ mod test {
         assert_eq!(set.cells(), &cells);
     }
 
+    #[test]
+    fn test_values() {
+        let mut cells: [Cell; 9] = std::array::from_fn(|i| Cell::new_guess(i as u8 + 1));
+        cells[0] = Cell::new_hint(1);
+        cells[4] = Cell::new_empty();
+        let set: Set<Row> = Set::new(cells, Position::ONE);
+
+        let values = set.values();
+        assert_eq!(values[4], None);
+        for (i, value) in values.iter().enumerate().filter(|(i, _)| *i != 4) {
+            assert_eq!(*value, Some(Value::from_index(i)));
+        }
+
+        let small: Set<Column, 4> = Set::new([Cell::new_empty(); 4], Position::TWO);
+        assert_eq!(small.values(), [None; 4]);
+    }
+
+    #[test]
+    fn test_four_cell_set() {
+        let cells = [
+            Cell::new_guess(3),
+            Cell::new_hint(1),
+            Cell::new_guess(4),
+            Cell::new_guess(2),
+        ];
+        let set: Set<Row, 4> = Set::new(cells, Position::ONE);
+        assert!(set.is_valid());
+        assert!(set.is_complete());
+
+        let incomplete: Set<Row, 4> = Set::new(
+            [
+                Cell::new_guess(3),
+                Cell::new_empty(),
+                Cell::new_guess(4),
+                Cell::new_guess(2),
+            ],
+            Position::ONE,
+        );
+        assert!(incomplete.is_valid());
+        assert!(!incomplete.is_complete());
+
+        // Distinct, but 9 doesn't belong in a unit of four
+        let out_of_range: Set<Box, 4> = Set::new(
+            [
+                Cell::new_guess(3),
+                Cell::new_guess(1),
+                Cell::new_guess(9),
+                Cell::new_guess(2),
+            ],
+            Position::ONE,
+        );
+        assert!(!out_of_range.is_valid());
+        assert!(!out_of_range.is_complete());
+    }
+
     #[test]
     fn test_set_type() {
         assert_eq!(Row::get_type(), "Row");
@@ -0,0 +1,449 @@
+use rand::{Rng, seq::SliceRandom};
+
+use super::{BitSet9, Cell, Point, Position, SudokuGrid, Value};
+
+impl SudokuGrid {
+    /// A random puzzle with a unique solution and about `clues` hints, see
+    /// [`SudokuGrid::generate_with_rng`]
+    pub fn generate(clues: usize) -> Self {
+        Self::generate_with_rng(clues, &mut rand::rng())
+    }
+
+    /// A random puzzle whose filled cells are all `Cell::Hint`s and which has exactly one
+    /// solution. A random full board is built with the solver, then cells are emptied in a
+    /// random order, skipping any whose removal would allow a second solution. Stops at
+    /// `clues` hints, or earlier if no more cells can go, so it may end up with more.
+    pub fn generate_with_rng<R: Rng + ?Sized>(clues: usize, rng: &mut R) -> Self {
+        // Any order of 1-9 is a valid first row, and the solver completes the rest
+        let mut first_row = Value::ALL_VALUES;
+        first_row.shuffle(rng);
+        let mut puzzle = Self::new();
+        for (y, value) in Position::ALL_POSITIONS.into_iter().zip(first_row) {
+            puzzle.set_cell(Point::new(Position::ONE, y), Cell::Hint(value));
+        }
+        let solution = puzzle
+            .solve()
+            .expect("a single valid row can always be completed");
+        for point in Position::all_board_positions() {
+            let value = solution.get_cell(point).value().expect("solution is full");
+            puzzle.set_cell(point, Cell::Hint(value));
+        }
+
+        let mut points: Vec<Point> = Position::all_board_positions().collect();
+        points.shuffle(rng);
+        let mut remaining = 81;
+        for point in points {
+            if remaining <= clues {
+                break;
+            }
+            let hint = puzzle.get_cell(point);
+            puzzle.set_cell(point, Cell::Empty);
+            if puzzle.find_solutions(2).len() == 1 {
+                remaining -= 1;
+            } else {
+                puzzle.set_cell(point, hint);
+            }
+        }
+        puzzle
+    }
+
+    /// Fills every empty cell with a `Cell::Guess` taken from the puzzle's solution.
+    /// Hints and existing guesses are left untouched.
+    pub fn fill_remaining(&mut self) -> Result<(), SolveError> {
+        if let Some(solution) = self.find_solutions(1).pop() {
+            *self = solution;
+            return Ok(());
+        }
+
+        // Work out whether the hints alone are already contradictory
+        let mut hints_only = *self;
+        for point in Position::all_board_positions() {
+            if hints_only.get_cell(point).is_guess() {
+                hints_only.set_cell(point, Cell::Empty);
+            }
+        }
+        if hints_only.find_solutions(1).is_empty() {
+            Err(SolveError::Unsolvable)
+        } else {
+            Err(SolveError::ConflictingGuesses)
+        }
+    }
+
+    /// A completed copy of the grid with every empty cell filled by a `Cell::Guess`, or
+    /// `None` if it has no solution. Existing cells are kept, so the result can go straight
+    /// to `Prover::new`.
+    pub fn solve(&self) -> Option<Self> {
+        self.find_solutions(1).pop()
+    }
+
+    /// Solves the grid, giving up with `SolveError::BudgetExceeded` once the search has
+    /// visited `max_steps` nodes. Guards against degenerate puzzles that make backtracking explode.
+    /// Returns `Ok(None)` if the grid has no solution.
+    pub fn solve_bounded(&self, max_steps: usize) -> Result<Option<Self>, SolveError> {
+        Ok(self.search_solutions(1, max_steps)?.pop())
+    }
+
+    /// Up to `limit` distinct complete grids extending this one, e.g. to show why a puzzle
+    /// is ambiguous. Every filled cell is kept fixed and the new cells are guesses.
+    pub fn solutions(&self, limit: usize) -> Vec<Self> {
+        self.find_solutions(limit)
+    }
+
+    /// Every step the solver takes on its way to the first solution, for step-through
+    /// visualisation: each guess placed and each guess taken back when its branch failed.
+    /// Replaying the steps onto this grid yields the solution. `None` if there is none.
+    pub fn solve_path(&self) -> Option<Vec<SolveStep>> {
+        let mut solver = Solver::new(1, usize::MAX);
+        solver.path = Some(Vec::new());
+        if self.is_valid_solution() {
+            let mut grid = *self;
+            // An unlimited budget can never be exceeded
+            solver.search(&mut grid).ok()?;
+        }
+        if solver.solutions.is_empty() {
+            return None;
+        }
+        solver.path
+    }
+
+    /// Finds up to `limit` completions of the grid, treating every filled cell as fixed.
+    fn find_solutions(&self, limit: usize) -> Vec<Self> {
+        // An unlimited budget can never be exceeded
+        self.search_solutions(limit, usize::MAX).unwrap_or_default()
+    }
+
+    fn search_solutions(&self, limit: usize, max_steps: usize) -> Result<Vec<Self>, SolveError> {
+        let mut solver = Solver::new(limit, max_steps);
+        if limit > 0 && self.is_valid_solution() {
+            let mut grid = *self;
+            solver.search(&mut grid)?;
+        }
+        Ok(solver.solutions)
+    }
+
+    /// The values not already used by any of `point`'s 20 peers in its row, column and box.
+    /// The cell's own value is ignored, so a filled cell only loses its value on a conflict.
+    pub fn candidates_at(&self, point: Point) -> BitSet9 {
+        let mut candidates = BitSet9::full();
+        for peer in point.peers() {
+            if let Some(value) = self.get_cell(peer).value() {
+                candidates.remove(value);
+            }
+        }
+        candidates
+    }
+
+    /// Returns the values that can be placed at `point` without clashing with its row, column or box.
+    fn candidates(&self, point: Point) -> Vec<Value> {
+        self.candidates_at(point).iter().collect()
+    }
+}
+
+/// One move of the solver, see [`SudokuGrid::solve_path`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolveStep {
+    /// A guess placed in an empty cell
+    Fill(Point, Value),
+    /// The guess at this point was taken back, as no solution follows from it
+    Revert(Point),
+}
+
+/// Recursive backtracking search that always branches on the most constrained empty cell.
+struct Solver {
+    limit: usize,
+    max_steps: usize,
+    steps: usize,
+    solutions: Vec<SudokuGrid>,
+    // Every fill and reversion, only recorded when `Some`
+    path: Option<Vec<SolveStep>>,
+}
+
+impl Solver {
+    fn new(limit: usize, max_steps: usize) -> Self {
+        Self {
+            limit,
+            max_steps,
+            steps: 0,
+            solutions: Vec::new(),
+            path: None,
+        }
+    }
+
+    fn search(&mut self, grid: &mut SudokuGrid) -> Result<(), SolveError> {
+        if self.steps >= self.max_steps {
+            return Err(SolveError::BudgetExceeded);
+        }
+        self.steps += 1;
+
+        let Some((point, candidates)) = Self::most_constrained(grid) else {
+            // No empty cells left, so the grid is complete
+            self.solutions.push(*grid);
+            return Ok(());
+        };
+
+        for value in candidates {
+            grid.set_cell(point, Cell::Guess(value));
+            self.record(SolveStep::Fill(point, value));
+            self.search(grid)?;
+            if self.solutions.len() >= self.limit {
+                break;
+            }
+            self.record(SolveStep::Revert(point));
+        }
+        grid.set_cell(point, Cell::Empty);
+        Ok(())
+    }
+
+    fn record(&mut self, step: SolveStep) {
+        if let Some(path) = &mut self.path {
+            path.push(step);
+        }
+    }
+
+    /// Picks the empty cell with the fewest candidates, or `None` if the grid is full.
+    fn most_constrained(grid: &SudokuGrid) -> Option<(Point, Vec<Value>)> {
+        let mut best: Option<(Point, Vec<Value>)> = None;
+        for point in Position::all_board_positions() {
+            if grid.get_cell(point).is_filled() {
+                continue;
+            }
+            let candidates = grid.candidates(point);
+            if candidates.len() <= 1 {
+                // Can't do better than a forced move or a dead end
+                return Some((point, candidates));
+            }
+            if best
+                .as_ref()
+                .is_none_or(|(_, b)| candidates.len() < b.len())
+            {
+                best = Some((point, candidates));
+            }
+        }
+        best
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum SolveError {
+    #[error("Puzzle has no solution")]
+    Unsolvable,
+    #[error("Existing guesses conflict with the puzzle's solution")]
+    ConflictingGuesses,
+    #[error("Solver step budget exceeded")]
+    BudgetExceeded,
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use super::*;
+
+    /// Turns every filled cell into a hint.
+    fn as_hints(mut grid: SudokuGrid) -> SudokuGrid {
+        for point in Position::all_board_positions() {
+            if let Some(value) = grid.get_cell(point).value() {
+                grid.set_cell(point, Cell::Hint(value));
+            }
+        }
+        grid
+    }
+
+    /// Parses a grid and turns every filled cell into a hint.
+    fn hints_from_str(s: &str) -> SudokuGrid {
+        as_hints(SudokuGrid::from_str(s).unwrap())
+    }
+
+    #[test]
+    fn test_fill_remaining_solvable() {
+        let mut puzzle = as_hints(SudokuGrid::test_solution().keep_every(2));
+        let original = puzzle;
+
+        puzzle.fill_remaining().unwrap();
+
+        assert!(puzzle.is_valid_solution());
+        let expected = SudokuGrid::test_solution();
+        for point in Position::all_board_positions() {
+            let before = original.get_cell(point);
+            let after = puzzle.get_cell(point);
+            assert_eq!(after.value(), expected.get_cell(point).value());
+            if before.is_hint() {
+                assert_eq!(before, after, "hints must be left untouched");
+            } else {
+                assert!(after.is_guess());
+            }
+        }
+    }
+
+    #[test]
+    fn test_solutions_two_completions() {
+        // Blank a rectangle of 9s and 4s spanning two boxes, which can be filled either way round
+        let points: Vec<Point> = Position::all_board_positions().collect();
+        let mut grid = SudokuGrid::test_solution();
+        for i in [1, 4, 2 * 9 + 1, 2 * 9 + 4] {
+            grid.set_cell(points[i], Cell::Empty);
+        }
+        let puzzle = as_hints(grid);
+
+        let solutions = puzzle.solutions(10);
+        assert_eq!(solutions.len(), 2);
+        assert_ne!(solutions[0], solutions[1]);
+        for solution in &solutions {
+            assert!(solution.is_full());
+            assert!(solution.is_valid_solution());
+            assert!(puzzle.hints_match(solution));
+        }
+
+        assert_eq!(puzzle.solutions(1).len(), 1);
+        assert!(puzzle.solutions(0).is_empty());
+    }
+
+    #[test]
+    fn test_generate() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let puzzle = SudokuGrid::generate_with_rng(32, &mut rng);
+        assert_eq!(puzzle.clue_count(), 32);
+        assert!(Position::all_board_positions().all(|p| !puzzle.get_cell(p).is_guess()));
+        assert_eq!(puzzle.solutions(2).len(), 1);
+
+        // Different draws give different puzzles
+        let other = SudokuGrid::generate_with_rng(32, &mut rng);
+        assert_ne!(puzzle, other);
+
+        // Asking for every cell leaves the full board
+        let full = SudokuGrid::generate(81);
+        assert!(full.is_full() && full.is_valid_solution());
+        assert_eq!(full.hints(), full);
+    }
+
+    #[test]
+    fn test_solve() {
+        let puzzle = as_hints(SudokuGrid::test_solution().keep_every(3));
+        let solution = puzzle.solve().unwrap();
+        assert!(solution.is_full());
+        assert!(solution.is_valid_solution());
+        assert!(puzzle.hints_match(&solution));
+        for point in Position::all_board_positions() {
+            let before = puzzle.get_cell(point);
+            assert_eq!(solution.get_cell(point).is_guess(), before.is_empty());
+        }
+        assert!(crate::Prover::new(&solution).is_ok());
+
+        let mut rows = String::from("12345678.........9");
+        rows.push_str(&".".repeat(81 - rows.len()));
+        assert_eq!(hints_from_str(&rows).solve(), None);
+    }
+
+    #[test]
+    fn test_solve_path() {
+        // Every other cell as a clue, which the solver fills without backtracking
+        let puzzle = as_hints(SudokuGrid::test_solution().keep_every(2));
+        let empties = 81 - puzzle.clue_count();
+        let path = puzzle.solve_path().unwrap();
+        assert_eq!(path.len(), empties);
+
+        let mut replayed = puzzle;
+        for step in path {
+            let SolveStep::Fill(point, value) = step else {
+                panic!("unexpected reversion {step:?}");
+            };
+            assert!(replayed.get_cell(point).is_empty());
+            replayed.set_cell(point, Cell::Guess(value));
+        }
+        assert_eq!(Some(replayed), puzzle.solutions(1).pop());
+
+        let mut rows = String::from("12345678.........9");
+        rows.push_str(&".".repeat(81 - rows.len()));
+        assert_eq!(hints_from_str(&rows).solve_path(), None);
+    }
+
+    #[test]
+    fn test_solve_path_replay() {
+        // Replaying fills and any reversions always ends on a solution
+        let puzzle = SudokuGrid::new();
+        let mut replayed = puzzle;
+        for step in puzzle.solve_path().unwrap() {
+            match step {
+                SolveStep::Fill(point, value) => replayed.set_cell(point, Cell::Guess(value)),
+                SolveStep::Revert(point) => replayed.set_cell(point, Cell::Empty),
+            }
+        }
+        assert!(replayed.is_full());
+        assert!(replayed.is_valid_solution());
+    }
+
+    #[test]
+    fn test_fill_remaining_unsolvable() {
+        // The last cell of the first row can only be 9, but column nine already has a 9
+        let mut rows = String::from("12345678.........9");
+        rows.push_str(&".".repeat(81 - rows.len()));
+        let mut puzzle = hints_from_str(&rows);
+        let original = puzzle;
+
+        assert_eq!(puzzle.fill_remaining(), Err(SolveError::Unsolvable));
+        assert_eq!(puzzle, original);
+    }
+
+    #[test]
+    fn test_solve_bounded_budget_exceeded() {
+        let empty = SudokuGrid::new();
+        assert_eq!(empty.solve_bounded(10), Err(SolveError::BudgetExceeded));
+
+        // A generous budget is enough to complete an empty grid
+        let solution = empty.solve_bounded(10_000).unwrap().unwrap();
+        assert!(solution.is_valid_solution());
+        assert!(Position::all_board_positions().all(|p| solution.get_cell(p).is_filled()));
+    }
+
+    #[test]
+    fn test_solve_bounded_unsolvable() {
+        let mut rows = String::from("12345678.........9");
+        rows.push_str(&".".repeat(81 - rows.len()));
+        let puzzle = hints_from_str(&rows);
+
+        assert_eq!(puzzle.solve_bounded(10_000), Ok(None));
+    }
+
+    #[test]
+    fn test_candidates_at() {
+        let solution = SudokuGrid::test_solution();
+        let point = Point::new(Position::FIVE, Position::FIVE);
+        let expected = solution.get_cell(point).value().unwrap();
+
+        // With only that cell cleared, its peers leave exactly the solution's value
+        let mut grid = solution;
+        grid.set_cell(point, Cell::Empty);
+        let candidates = grid.candidates_at(point);
+        assert_eq!(candidates.len(), 1);
+        assert!(candidates.contains(expected));
+
+        assert_eq!(SudokuGrid::new().candidates_at(point), BitSet9::full());
+
+        // A filled cell clashing with a row peer: the peer's old value still appears in the
+        // cell's column, so every value is now taken
+        let peer = Point::new(point.x(), Position::ONE);
+        grid.set_cell(point, Cell::Guess(expected));
+        grid.set_cell(peer, Cell::Guess(expected));
+        assert!(grid.candidates_at(point).is_empty());
+    }
+
+    #[test]
+    fn test_fill_remaining_conflicting_guesses() {
+        let mut puzzle = as_hints(SudokuGrid::test_solution().keep_every(2));
+        // Place a wrong (but locally valid) guess in an empty cell that isn't forced
+        let point = Position::all_board_positions()
+            .find(|p| puzzle.get_cell(*p).is_empty() && puzzle.candidates(*p).len() > 1)
+            .unwrap();
+        let expected = SudokuGrid::test_solution().get_cell(point);
+        let wrong = puzzle
+            .candidates(point)
+            .into_iter()
+            .find(|v| Some(*v) != expected.value())
+            .unwrap();
+        puzzle.set_cell(point, Cell::Guess(wrong));
+
+        assert_eq!(puzzle.fill_remaining(), Err(SolveError::ConflictingGuesses));
+    }
+}
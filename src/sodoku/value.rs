@@ -1,9 +1,11 @@
 use core::fmt;
+use std::str::FromStr;
 
 use num_traits::NumCast;
 
 /// Represents the values in a Sudoku grid.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Value {
     One,
     Two,
@@ -76,6 +78,34 @@ impl Value {
             _ => panic!("Invalid index for value: {}", index),
         }
     }
+
+    /// Adds `n`, cycling within 1-9 so `Nine + 1` is `One`
+    pub fn wrapping_add(self, n: u8) -> Self {
+        Self::from_index((self.to_index() + (n % 9) as usize) % 9)
+    }
+
+    /// Subtracts `n`, cycling within 1-9 so `One - 1` is `Nine`
+    pub fn wrapping_sub(self, n: u8) -> Self {
+        Self::from_index((self.to_index() + 9 - (n % 9) as usize) % 9)
+    }
+
+    /// Adds `n`, or `None` past Nine
+    pub fn checked_add(self, n: u8) -> Option<Self> {
+        let index = self.to_index() + n as usize;
+        (index < 9).then(|| Self::from_index(index))
+    }
+
+    /// Subtracts `n`, or `None` below One
+    pub fn checked_sub(self, n: u8) -> Option<Self> {
+        self.to_index()
+            .checked_sub(n as usize)
+            .map(Self::from_index)
+    }
+
+    /// Endless round-robin of values starting at `self`, wrapping from Nine back to One
+    pub fn cycle_from(self) -> impl Iterator<Item = Value> {
+        Self::ALL_VALUES.into_iter().cycle().skip(self.to_index())
+    }
 }
 
 impl Value {
@@ -100,6 +130,68 @@ impl Value {
             None => panic!("Invalid value: {}, cannot be converted to u8", num),
         }
     }
+
+    /// Like [`Value::new`], but returns an error for anything other than a whole number 1-9
+    /// instead of panicking. Fractional floats are rejected rather than truncated.
+    pub fn try_new<T: NumCast>(value: T) -> Result<Self, ValueError> {
+        let number = value.to_f64().ok_or(ValueError::NotConvertible)?;
+        if number.fract() != 0.0 {
+            return Err(ValueError::NotAnInteger(number));
+        }
+        match value.to_u8() {
+            Some(n @ 1..=9) => Ok(Self::from_index(n as usize - 1)),
+            _ => Err(ValueError::OutOfRange(number)),
+        }
+    }
+}
+
+impl Value {
+    /// Creates a Value from a digit checked at compile time, so `Value::new_const::<10>()`
+    /// is a build error rather than a runtime panic.
+    ///
+    /// ```
+    /// use zk_sudoku_prover::Value;
+    /// assert_eq!(Value::new_const::<5>(), Value::Five);
+    /// ```
+    ///
+    /// ```compile_fail
+    /// let _ = zk_sudoku_prover::Value::new_const::<0>();
+    /// ```
+    ///
+    /// ```compile_fail
+    /// let _ = zk_sudoku_prover::Value::new_const::<10>();
+    /// ```
+    pub const fn new_const<const N: u8>() -> Self {
+        const { assert!(N >= 1 && N <= 9, "Value must be between 1 and 9") };
+        match N {
+            1 => Value::One,
+            2 => Value::Two,
+            3 => Value::Three,
+            4 => Value::Four,
+            5 => Value::Five,
+            6 => Value::Six,
+            7 => Value::Seven,
+            8 => Value::Eight,
+            _ => Value::Nine,
+        }
+    }
+}
+
+/// Builds a [`Value`] from a digit literal, checked at compile time.
+///
+/// ```
+/// use zk_sudoku_prover::{Value, value};
+/// assert_eq!(value!(7), Value::Seven);
+/// ```
+///
+/// ```compile_fail
+/// let _ = zk_sudoku_prover::value!(10);
+/// ```
+#[macro_export]
+macro_rules! value {
+    ($n:literal) => {
+        $crate::Value::new_const::<$n>()
+    };
 }
 
 // Implement TryFrom for common integer types
@@ -133,6 +225,31 @@ impl From<char> for Value {
     }
 }
 
+impl FromStr for Value {
+    type Err = ValueError;
+
+    /// Parses the `Display` form of a value, a single digit 1-9
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c @ '1'..='9'), None) => Ok(Value::from(c)),
+            _ => Err(ValueError::InvalidValue(s.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum ValueError {
+    #[error("Invalid value: {0}, must be between 1 and 9")]
+    OutOfRange(f64),
+    #[error("Invalid value: {0}, must be a whole number")]
+    NotAnInteger(f64),
+    #[error("Invalid value: cannot be converted to a number")]
+    NotConvertible,
+    #[error("Invalid value: {0:?}, expected a single digit 1-9")]
+    InvalidValue(String),
+}
+
 impl std::fmt::Display for Value {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -148,3 +265,100 @@ impl std::fmt::Display for Value {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_try_new() {
+        assert_eq!(Value::try_new(1u8), Ok(Value::One));
+        assert_eq!(Value::try_new(9i64), Ok(Value::Nine));
+        assert_eq!(Value::try_new(5usize), Ok(Value::Five));
+        assert_eq!(Value::try_new(3.0f64), Ok(Value::Three));
+
+        assert_eq!(Value::try_new(0), Err(ValueError::OutOfRange(0.0)));
+        assert_eq!(Value::try_new(10u32), Err(ValueError::OutOfRange(10.0)));
+        assert_eq!(Value::try_new(-1i8), Err(ValueError::OutOfRange(-1.0)));
+        assert_eq!(Value::try_new(256u16), Err(ValueError::OutOfRange(256.0)));
+        assert_eq!(Value::try_new(3.5f32), Err(ValueError::NotAnInteger(3.5)));
+        assert!(matches!(
+            Value::try_new(f64::NAN),
+            Err(ValueError::NotAnInteger(_))
+        ));
+    }
+
+    #[test]
+    fn test_new_const() {
+        const FOUR: Value = Value::new_const::<4>();
+        assert_eq!(FOUR, Value::Four);
+        assert_eq!(crate::value!(1), Value::One);
+        assert_eq!(crate::value!(9), Value::Nine);
+        for (i, value) in [
+            crate::value!(1),
+            crate::value!(2),
+            crate::value!(3),
+            crate::value!(4),
+            crate::value!(5),
+            crate::value!(6),
+            crate::value!(7),
+            crate::value!(8),
+            crate::value!(9),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            assert_eq!(value, Value::from_index(i));
+        }
+    }
+
+    #[test]
+    fn test_cycle_from() {
+        let values: Vec<_> = Value::Eight.cycle_from().take(11).collect();
+        assert_eq!(values[0], Value::Eight);
+        assert_eq!(values[1], Value::Nine);
+        assert_eq!(values[2], Value::One);
+        assert_eq!(values[10], Value::Nine);
+        for (i, value) in values.iter().enumerate() {
+            assert_eq!(*value, Value::from_index((7 + i) % 9));
+        }
+        assert_eq!(Value::One.cycle_from().nth(9), Some(Value::One));
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        assert_eq!(Value::Nine.wrapping_add(1), Value::One);
+        assert_eq!(Value::Three.wrapping_add(4), Value::Seven);
+        assert_eq!(Value::Five.wrapping_add(9), Value::Five);
+        assert_eq!(Value::Two.wrapping_add(255), Value::Five);
+        assert_eq!(Value::One.wrapping_sub(1), Value::Nine);
+        assert_eq!(Value::Seven.wrapping_sub(16), Value::Nine);
+
+        assert_eq!(Value::Nine.checked_add(1), None);
+        assert_eq!(Value::Eight.checked_add(1), Some(Value::Nine));
+        assert_eq!(Value::One.checked_add(255), None);
+        assert_eq!(Value::One.checked_sub(1), None);
+        assert_eq!(Value::Nine.checked_sub(8), Some(Value::One));
+
+        for value in Value::ALL_VALUES {
+            for n in 0..20 {
+                assert_eq!(value.wrapping_add(n).wrapping_sub(n), value);
+            }
+        }
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!(Value::from_str("5").unwrap(), Value::Five);
+        for value in Value::ALL_VALUES {
+            assert_eq!(value.to_string().parse::<Value>().unwrap(), value);
+        }
+
+        for invalid in ["0", "10", "x", ""] {
+            assert!(matches!(
+                Value::from_str(invalid),
+                Err(ValueError::InvalidValue(_))
+            ));
+        }
+    }
+}
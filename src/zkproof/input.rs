@@ -0,0 +1,143 @@
+use bytes::Bytes;
+
+use crate::{Cell, Position, PuzzleDigest, SudokuGrid};
+
+use super::ZkProofError;
+
+/// The two halves of a proof: the public puzzle and the prover's secret solution.
+/// Every filled cell of the puzzle is a clue and is stored as a `Cell::Hint`, while the
+/// solution holds the same hints plus a `Cell::Guess` for every other cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProofInput {
+    puzzle: SudokuGrid,
+    solution: SudokuGrid,
+}
+
+impl ProofInput {
+    /// Pair a public puzzle with a complete solution, checking the solution completes it.
+    pub fn new(puzzle: &SudokuGrid, solution: &SudokuGrid) -> Result<Self, ZkProofError> {
+        solution.validate()?;
+
+        let mut clues = SudokuGrid::new();
+        let mut secret = SudokuGrid::new();
+        for point in Position::all_board_positions() {
+            let value = solution
+                .get_cell(point)
+                .value()
+                .ok_or_else(|| ZkProofError::SudokuError("Solution is incomplete".to_string()))?;
+
+            match puzzle.get_cell(point).value() {
+                Some(clue) if clue != value => {
+                    return Err(ZkProofError::SudokuError(
+                        "Solution does not complete the puzzle".to_string(),
+                    ));
+                }
+                Some(_) => {
+                    clues.set_cell(point, Cell::Hint(value));
+                    secret.set_cell(point, Cell::Hint(value));
+                }
+                None => secret.set_cell(point, Cell::Guess(value)),
+            }
+        }
+
+        Ok(Self {
+            puzzle: clues,
+            solution: secret,
+        })
+    }
+
+    /// Use the hints of a complete grid as the puzzle and the whole grid as the solution
+    pub fn from_solution(solution: &SudokuGrid) -> Result<Self, ZkProofError> {
+        let mut puzzle = SudokuGrid::new();
+        for point in Position::all_board_positions() {
+            let cell = solution.get_cell(point);
+            if cell.is_hint() {
+                puzzle.set_cell(point, cell);
+            }
+        }
+        Self::new(&puzzle, solution)
+    }
+
+    /// The public puzzle, which the verifier's graph is built from
+    pub fn puzzle(&self) -> &SudokuGrid {
+        &self.puzzle
+    }
+
+    /// Blake3 digest of the public puzzle's hints, one byte per cell in row-major order
+    /// with 0 for an empty cell, so both parties can confirm they hold the same puzzle
+    pub fn puzzle_digest(&self) -> PuzzleDigest {
+        let mut hasher = blake3::Hasher::new();
+        for cell in &self.puzzle {
+            hasher.update(&[cell.value().map_or(0, |value| value.to_numeric())]);
+        }
+        PuzzleDigest::new(Bytes::copy_from_slice(hasher.finalize().as_bytes()))
+    }
+
+    /// The prover's secret solution
+    pub fn solution(&self) -> &SudokuGrid {
+        &self.solution
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_proof_input_consistent() {
+        let solution = SudokuGrid::test_solution();
+        let input = ProofInput::new(&solution.keep_every(3), &solution).unwrap();
+
+        for point in Position::all_board_positions() {
+            let clue = input.puzzle().get_cell(point);
+            let secret = input.solution().get_cell(point);
+            assert!(clue.is_empty() || clue.is_hint());
+            assert_eq!(secret.value(), solution.get_cell(point).value());
+            assert_eq!(secret.is_hint(), clue.is_hint());
+        }
+    }
+
+    #[test]
+    fn test_puzzle_digest() {
+        let solution = SudokuGrid::test_solution();
+        let input = ProofInput::new(&solution.keep_every(3), &solution).unwrap();
+        let digest = input.puzzle_digest();
+        assert_eq!(digest.as_bytes().len(), 32);
+        assert_eq!(digest.to_string().len(), 64);
+
+        // The solution doesn't enter the digest, the hints do
+        assert_eq!(
+            ProofInput::new(input.puzzle(), &solution)
+                .unwrap()
+                .puzzle_digest(),
+            digest
+        );
+        let fewer_hints = ProofInput::new(&SudokuGrid::new(), &solution).unwrap();
+        assert_ne!(fewer_hints.puzzle_digest(), digest);
+    }
+
+    #[test]
+    fn test_proof_input_inconsistent_solution_rejected() {
+        let solution = SudokuGrid::test_solution();
+
+        // Give the first clue the next clue's digit so it disagrees with the solution
+        let mut puzzle = solution.keep_every(3);
+        let mut points = Position::all_board_positions();
+        let (first, next) = (points.next().unwrap(), points.nth(2).unwrap());
+        puzzle.set_cell(first, solution.get_cell(next));
+
+        assert!(matches!(
+            ProofInput::new(&puzzle, &solution),
+            Err(ZkProofError::SudokuError(_))
+        ));
+    }
+
+    #[test]
+    fn test_proof_input_incomplete_solution_rejected() {
+        let puzzle = SudokuGrid::test_solution().keep_every(3);
+        assert!(matches!(
+            ProofInput::new(&puzzle, &puzzle),
+            Err(ZkProofError::SudokuError(_))
+        ));
+    }
+}
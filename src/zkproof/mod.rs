@@ -1,9 +1,17 @@
+mod input;
+mod multi;
 mod protocol;
 mod prover;
+mod transcript;
 mod types;
+mod verification_key;
 mod verifier;
 
+pub use input::*;
+pub use multi::*;
 pub use protocol::*;
 pub use prover::*;
+pub use transcript::*;
 pub use types::*;
+pub use verification_key::*;
 pub use verifier::*;
@@ -0,0 +1,212 @@
+use std::collections::HashMap;
+
+use super::{
+    Prover, ProverCommitment, ProverResponse, Verifier, VerifierChallenge, VerifierResult,
+    ZkProofError,
+};
+
+/// Identifies one of several puzzles proven over the same session
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PuzzleId(pub usize);
+
+/// A protocol message addressed to one puzzle of a multi-puzzle session
+#[derive(Debug, Clone)]
+pub enum ProtocolMessage {
+    Commitment {
+        puzzle: PuzzleId,
+        commitment: ProverCommitment,
+    },
+    Challenge {
+        puzzle: PuzzleId,
+        challenge: VerifierChallenge,
+    },
+    Response {
+        puzzle: PuzzleId,
+        response: ProverResponse,
+    },
+    Result {
+        puzzle: PuzzleId,
+        result: VerifierResult,
+    },
+}
+
+impl ProtocolMessage {
+    /// The puzzle this message belongs to
+    pub fn puzzle(&self) -> PuzzleId {
+        match self {
+            Self::Commitment { puzzle, .. }
+            | Self::Challenge { puzzle, .. }
+            | Self::Response { puzzle, .. }
+            | Self::Result { puzzle, .. } => *puzzle,
+        }
+    }
+}
+
+/// The prover's side of a session proving several puzzles, one [`Prover`] per puzzle
+#[derive(Default)]
+pub struct MultiProver {
+    provers: HashMap<PuzzleId, Prover>,
+}
+
+impl MultiProver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a puzzle's prover. The verifier side must use the same id for the puzzle.
+    pub fn insert(&mut self, puzzle: PuzzleId, prover: Prover) {
+        self.provers.insert(puzzle, prover);
+    }
+
+    /// Commit to a fresh colouring for one puzzle
+    pub fn start_round(&mut self, puzzle: PuzzleId) -> Result<ProtocolMessage, ZkProofError> {
+        let commitment = self.prover_mut(puzzle)?.start_round();
+        Ok(ProtocolMessage::Commitment { puzzle, commitment })
+    }
+
+    /// Answer a challenge with the matching response. Any other message is rejected.
+    pub fn handle(&mut self, message: ProtocolMessage) -> Result<ProtocolMessage, ZkProofError> {
+        match message {
+            ProtocolMessage::Challenge { puzzle, challenge } => {
+                let response = self.prover_mut(puzzle)?.respond_to_challenge(challenge)?;
+                Ok(ProtocolMessage::Response { puzzle, response })
+            }
+            _ => Err(ZkProofError::UnexpectedMessage),
+        }
+    }
+
+    fn prover_mut(&mut self, puzzle: PuzzleId) -> Result<&mut Prover, ZkProofError> {
+        self.provers
+            .get_mut(&puzzle)
+            .ok_or(ZkProofError::UnknownPuzzle(puzzle.0))
+    }
+}
+
+/// The verifier's side of a session proving several puzzles, one [`Verifier`] per puzzle.
+/// Rounds are counted across the whole session, but confidence is per puzzle.
+#[derive(Default)]
+pub struct MultiVerifier {
+    verifiers: HashMap<PuzzleId, Verifier>,
+}
+
+impl MultiVerifier {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a puzzle's verifier, under the id the prover uses for it
+    pub fn insert(&mut self, puzzle: PuzzleId, verifier: Verifier) {
+        self.verifiers.insert(puzzle, verifier);
+    }
+
+    /// Challenge a commitment or check a response, routed to the puzzle's verifier.
+    /// Any other message is rejected.
+    pub fn handle(&mut self, message: ProtocolMessage) -> Result<ProtocolMessage, ZkProofError> {
+        match message {
+            ProtocolMessage::Commitment { puzzle, commitment } => {
+                let challenge = self.verifier_mut(puzzle)?.receive_commitment(commitment)?;
+                Ok(ProtocolMessage::Challenge { puzzle, challenge })
+            }
+            ProtocolMessage::Response { puzzle, response } => {
+                let result = self.verifier_mut(puzzle)?.verify_response(response)?;
+                Ok(ProtocolMessage::Result { puzzle, result })
+            }
+            _ => Err(ZkProofError::UnexpectedMessage),
+        }
+    }
+
+    pub fn verifier(&self, puzzle: PuzzleId) -> Option<&Verifier> {
+        self.verifiers.get(&puzzle)
+    }
+
+    /// Confidence as a percentage for one puzzle, `None` for an unknown puzzle
+    pub fn confidence(&self, puzzle: PuzzleId) -> Option<f64> {
+        self.verifier(puzzle).map(Verifier::confidence_level)
+    }
+
+    /// Rounds run across every puzzle of the session
+    pub fn rounds_run(&self) -> usize {
+        self.verifiers.values().map(Verifier::rounds_run).sum()
+    }
+
+    fn verifier_mut(&mut self, puzzle: PuzzleId) -> Result<&mut Verifier, ZkProofError> {
+        self.verifiers
+            .get_mut(&puzzle)
+            .ok_or(ZkProofError::UnknownPuzzle(puzzle.0))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use crate::{Graph, ProofInput, SudokuGrid, ZKProtocol};
+
+    use super::*;
+
+    const INPUT: &str = include_str!("../../data/validation.csv");
+
+    fn create_input(line: &str) -> ProofInput {
+        let solution = SudokuGrid::from_str(line).unwrap();
+        ProofInput::new(&solution.keep_every(3), &solution).unwrap()
+    }
+
+    #[test]
+    fn test_multi_puzzle_session() {
+        let mut multi_prover = MultiProver::new();
+        let mut multi_verifier = MultiVerifier::new();
+        let puzzles: Vec<PuzzleId> = (0..3).map(PuzzleId).collect();
+        for (puzzle, line) in puzzles.iter().zip(INPUT.lines()) {
+            let input = create_input(line);
+            let (prover, _) = Prover::new(input.solution()).unwrap();
+            let graph = Graph::from_puzzle(input.puzzle());
+            multi_prover.insert(*puzzle, prover);
+            multi_verifier.insert(
+                *puzzle,
+                Verifier::new_with_node_count(graph.edge_map(), graph.node_count()),
+            );
+        }
+
+        let target = 25.0;
+        let edge_count = multi_verifier.verifier(puzzles[0]).unwrap().edge_map_len();
+        let rounds = ZKProtocol::calculate_rounds_needed(edge_count, target);
+
+        // Interleave the puzzles round by round
+        for _ in 0..rounds {
+            for puzzle in &puzzles {
+                let commitment = multi_prover.start_round(*puzzle).unwrap();
+                let challenge = multi_verifier.handle(commitment).unwrap();
+                assert_eq!(challenge.puzzle(), *puzzle);
+                let response = multi_prover.handle(challenge).unwrap();
+                let ProtocolMessage::Result { puzzle: id, result } =
+                    multi_verifier.handle(response).unwrap()
+                else {
+                    panic!("expected a result");
+                };
+                assert_eq!(id, *puzzle);
+                assert!(result.success);
+            }
+        }
+
+        assert_eq!(multi_verifier.rounds_run(), 3 * rounds);
+        for puzzle in &puzzles {
+            assert_eq!(
+                multi_verifier.verifier(*puzzle).unwrap().rounds_run(),
+                rounds
+            );
+            assert!(multi_verifier.confidence(*puzzle).unwrap() >= target);
+        }
+
+        // Messages for unknown puzzles, or for the other party, are refused
+        assert!(matches!(
+            multi_prover.start_round(PuzzleId(7)),
+            Err(ZkProofError::UnknownPuzzle(7))
+        ));
+        let commitment = multi_prover.start_round(puzzles[0]).unwrap();
+        assert!(matches!(
+            multi_prover.handle(commitment),
+            Err(ZkProofError::UnexpectedMessage)
+        ));
+        assert_eq!(multi_verifier.confidence(PuzzleId(7)), None);
+    }
+}
@@ -1,17 +1,69 @@
-use crate::SudokuGrid;
+use std::sync::atomic::{AtomicBool, Ordering};
 
-use super::{Prover, Verifier, VerifierResult, ZkProofError};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use rand_chacha::ChaCha20Rng;
+
+use crate::{CommitmentError, DEFAULT_NONCE_LEN, Graph, MIN_NONCE_LEN};
+
+use super::{ProofInput, Prover, Verifier, VerifierResult, ZkProofError};
+
+/// How [`ZKProtocol::run`] picks the edges to challenge
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChallengeStrategy {
+    /// One uniformly random edge per round, for as many rounds as the confidence needs
+    #[default]
+    Random,
+    /// Every edge exactly once, in a shuffled order
+    Exhaustive,
+}
+
+/// How [`ZKProtocol::run_proof_cancellable`] ended
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProofOutcome {
+    /// Every requested round passed
+    Accepted,
+    /// A round failed verification
+    Rejected,
+    /// The cancel flag was set before every round had run
+    Cancelled {
+        /// Rounds the verifier had run in total when the proof stopped
+        rounds_run: usize,
+        /// The verifier's confidence from those rounds
+        confidence: f64,
+    },
+}
 
 pub struct ZKProtocol {
     prover: Prover,
     verifier: Verifier,
+    confidence: f64,
+    challenge_strategy: ChallengeStrategy,
+    // Orders the challenges of an exhaustive run
+    challenge_rng: StdRng,
 }
 
 impl ZKProtocol {
-    pub fn new(puzzle: &SudokuGrid) -> Result<Self, ZkProofError> {
-        let (prover, edge_map) = Prover::new(puzzle)?;
-        let verifier = Verifier::new(edge_map);
-        Ok(Self { prover, verifier })
+    /// The prover colours the graph with the secret solution, while the verifier's
+    /// edge map is built from the public puzzle alone.
+    pub fn new(input: &ProofInput) -> Result<Self, ZkProofError> {
+        ZKProtocolBuilder::new().build(input)
+    }
+
+    pub fn builder() -> ZKProtocolBuilder {
+        ZKProtocolBuilder::new()
+    }
+
+    /// Run the proof with the configured confidence and challenge strategy
+    pub fn run(&mut self) -> Result<bool, ZkProofError> {
+        match self.challenge_strategy {
+            ChallengeStrategy::Random => self.prove_with_confidence(self.confidence),
+            ChallengeStrategy::Exhaustive => {
+                let mut rng = self.challenge_rng.clone();
+                let result = self.run_exhaustive_shuffled(&mut rng);
+                self.challenge_rng = rng;
+                result
+            }
+        }
     }
 
     pub fn run_round(&mut self) -> Result<VerifierResult, ZkProofError> {
@@ -40,10 +92,57 @@ impl ZKProtocol {
         Ok(true) // All rounds successful
     }
 
+    /// Like [`ZKProtocol::run_proof`], but checks `cancel` before each round and stops with
+    /// [`ProofOutcome::Cancelled`] once it's set, e.g. from a UI's cancel button.
+    /// Zero rounds is an error rather than a vacuous acceptance.
+    pub fn run_proof_cancellable(
+        &mut self,
+        num_rounds: usize,
+        cancel: &AtomicBool,
+    ) -> Result<ProofOutcome, ZkProofError> {
+        if num_rounds == 0 {
+            return Err(ZkProofError::ZeroRounds);
+        }
+        for _ in 0..num_rounds {
+            if cancel.load(Ordering::Relaxed) {
+                return Ok(ProofOutcome::Cancelled {
+                    rounds_run: self.rounds_run(),
+                    confidence: self.confidence(),
+                });
+            }
+            if !self.run_round()?.success {
+                return Ok(ProofOutcome::Rejected);
+            }
+        }
+        Ok(ProofOutcome::Accepted)
+    }
+
+    /// Challenge every edge exactly once, in an order drawn from `rng`, one round per edge
+    pub fn run_exhaustive_shuffled<R: Rng + ?Sized>(
+        &mut self,
+        rng: &mut R,
+    ) -> Result<bool, ZkProofError> {
+        for edge in self.verifier.challenge_permutation(rng) {
+            let commitments = self.prover.start_round();
+            let challenge = self
+                .verifier
+                .receive_commitment_for_edge(commitments, edge)?;
+            let response = self.prover.respond_to_challenge(challenge)?;
+            if !self.verifier.verify_response(response)?.success {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
     pub fn prove_with_confidence(&mut self, confidence: f64) -> Result<bool, ZkProofError> {
         println!("Desired confidence: {}", confidence);
         let edge_count = self.verifier.edge_map_len();
         let rounds_needed = Self::calculate_rounds_needed(edge_count, confidence);
+        if rounds_needed == 0 {
+            // Running zero rounds would report success having proven nothing
+            return Err(ZkProofError::ZeroRounds);
+        }
         println!(
             "Running {} rounds for {:.2}% confidence",
             rounds_needed, confidence
@@ -51,11 +150,134 @@ impl ZKProtocol {
         self.run_proof(rounds_needed)
     }
 
+    /// The verifier's confidence so far, see [`Verifier::confidence_level`]
+    pub fn confidence(&self) -> f64 {
+        self.verifier.confidence_level()
+    }
+
+    /// Number of rounds the verifier has run so far
+    pub fn rounds_run(&self) -> usize {
+        self.verifier.rounds_run()
+    }
+
+    /// Rounds for 99.99% soundness on this proof's graph. Soundness depends only on the edge
+    /// count, as each round challenges one edge out of all of them; how hard the puzzle is
+    /// to solve plays no part. Each clue adds eight anchoring edges, so a harder puzzle with
+    /// fewer clues needs slightly fewer rounds, not more.
+    pub fn recommended_rounds(&self) -> usize {
+        Self::calculate_rounds_needed(self.verifier.edge_map_len(), 99.99)
+    }
+
     pub fn calculate_rounds_needed(edge_count: usize, confidence: f64) -> usize {
         let catch_prob = 1.0 / (edge_count as f64);
         let log_term = (1.0 - confidence / 100.0).ln() / (1.0 - catch_prob).ln();
         log_term.ceil() as usize
     }
+
+    /// Rounds needed for `bits` of soundness, i.e. the fewest with `(1 - 1/E)^rounds <= 2^-bits`
+    pub fn rounds_for_soundness_bits(edge_count: usize, bits: u32) -> usize {
+        if bits == 0 {
+            return 0;
+        }
+        if edge_count <= 1 {
+            // A single edge is always challenged, so one round catches any cheat
+            return 1;
+        }
+        // ln_1p keeps precision when 1/E is tiny
+        let per_round = -(-1.0 / edge_count as f64).ln_1p();
+        (f64::from(bits) * std::f64::consts::LN_2 / per_round).ceil() as usize
+    }
+}
+
+/// Chainable configuration for a [`ZKProtocol`]
+#[derive(Debug, Clone)]
+pub struct ZKProtocolBuilder {
+    confidence: f64,
+    seed: Option<u64>,
+    nonce_len: usize,
+    challenge_strategy: ChallengeStrategy,
+}
+
+impl Default for ZKProtocolBuilder {
+    fn default() -> Self {
+        Self {
+            confidence: 99.0,
+            seed: None,
+            nonce_len: DEFAULT_NONCE_LEN,
+            challenge_strategy: ChallengeStrategy::default(),
+        }
+    }
+}
+
+impl ZKProtocolBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Target confidence, as a percentage, for [`ChallengeStrategy::Random`]
+    pub fn confidence(mut self, confidence: f64) -> Self {
+        self.confidence = confidence;
+        self
+    }
+
+    /// Seed the prover's colour shuffles and the verifier's challenges, for reproducible runs.
+    /// Anyone who knows the seed can predict the shuffles, so don't use this for real proofs.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Length in bytes of the prover's commitment nonces
+    pub fn nonce_len(mut self, nonce_len: usize) -> Self {
+        self.nonce_len = nonce_len;
+        self
+    }
+
+    pub fn challenge_strategy(mut self, challenge_strategy: ChallengeStrategy) -> Self {
+        self.challenge_strategy = challenge_strategy;
+        self
+    }
+
+    /// Nonces shorter than [`MIN_NONCE_LEN`] are rejected here, as the verifier would
+    /// refuse every reveal anyway. So are puzzles without hints: with nothing anchoring the
+    /// colours, any proper colouring would pass and the proof would say nothing about the puzzle.
+    pub fn build(self, input: &ProofInput) -> Result<ZKProtocol, ZkProofError> {
+        if input.puzzle().clue_count() == 0 {
+            return Err(ZkProofError::SudokuError("no clues to prove".to_string()));
+        }
+        if self.nonce_len < MIN_NONCE_LEN {
+            return Err(CommitmentError::NonceTooShort {
+                len: self.nonce_len,
+                min: MIN_NONCE_LEN,
+            }
+            .into());
+        }
+
+        let (mut prover, challenge_rng) = match self.seed {
+            Some(seed) => (
+                Prover::new_with_rng(input.solution(), ChaCha20Rng::seed_from_u64(seed))?.0,
+                StdRng::seed_from_u64(seed),
+            ),
+            None => (
+                Prover::new(input.solution())?.0,
+                StdRng::from_rng(&mut rand::rng()),
+            ),
+        };
+        prover.set_nonce_len(self.nonce_len);
+
+        let graph = Graph::from_puzzle(input.puzzle());
+        let mut verifier = Verifier::new_with_node_count(graph.edge_map(), graph.node_count());
+        if let Some(seed) = self.seed {
+            verifier.set_seed(seed);
+        }
+        Ok(ZKProtocol {
+            prover,
+            verifier,
+            confidence: self.confidence,
+            challenge_strategy: self.challenge_strategy,
+            challenge_rng,
+        })
+    }
 }
 
 // Setup prover
@@ -75,3 +297,247 @@ impl ZKProtocol {
 // Prover receives the edge and reveals the commitments for the two nodes connected by that edge.
 //
 // Verifier receives the revealed commitments and checks if they are valid.
+
+#[cfg(test)]
+mod test {
+    use std::{str::FromStr, sync::Barrier, thread, time::Duration};
+
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use crate::SudokuGrid;
+
+    use super::*;
+
+    fn create_valid_input() -> ProofInput {
+        let solution = SudokuGrid::test_solution();
+        let puzzle = SudokuGrid::from_str(
+            "2..5..3..8..2..6..7..6..2..9..7..8..3..1..9..6..8..5..1..4..7..4..3..1..5..9..4..",
+        )
+        .unwrap();
+        ProofInput::new(&puzzle, &solution).unwrap()
+    }
+
+    #[test]
+    fn test_verifier_graph_from_puzzle() {
+        let input = create_valid_input();
+        let (_, prover_edges) = Prover::new(input.solution()).unwrap();
+        let protocol = ZKProtocol::new(&input).unwrap();
+
+        assert_eq!(protocol.verifier.edge_map_len(), prover_edges.len());
+        assert_eq!(Graph::from_sudoku(input.puzzle()).edge_map(), prover_edges);
+    }
+
+    #[test]
+    fn test_protocol_proves_input() {
+        let mut protocol = ZKProtocol::new(&create_valid_input()).unwrap();
+        assert!(protocol.run_proof(20).unwrap());
+    }
+
+    #[test]
+    fn test_run_exhaustive_shuffled() {
+        let mut protocol = ZKProtocol::new(&create_valid_input()).unwrap();
+        let mut rng = StdRng::seed_from_u64(1);
+        assert!(protocol.run_exhaustive_shuffled(&mut rng).unwrap());
+
+        let metrics = protocol.verifier.metrics();
+        assert_eq!(metrics.rounds_total, metrics.edges_total);
+        assert_eq!(metrics.unique_edges_challenged, metrics.edges_total);
+        assert_eq!(metrics.rounds_passed, metrics.edges_total);
+    }
+
+    #[test]
+    fn test_confidence_passthrough() {
+        let mut protocol = ZKProtocol::new(&create_valid_input()).unwrap();
+        assert_eq!(protocol.confidence(), 0.0);
+        assert_eq!(protocol.rounds_run(), 0);
+
+        assert!(protocol.run_proof(50).unwrap());
+        assert_eq!(protocol.rounds_run(), 50);
+        assert!(protocol.confidence() > 0.0);
+        assert_eq!(protocol.confidence(), protocol.verifier.confidence_level());
+    }
+
+    #[test]
+    fn test_rounds_for_soundness_bits() {
+        let edges = Graph::from_sudoku(create_valid_input().puzzle()).edge_count();
+        let rounds_40 = ZKProtocol::rounds_for_soundness_bits(edges, 40);
+        let rounds_80 = ZKProtocol::rounds_for_soundness_bits(edges, 80);
+
+        // Roughly E * bits * ln 2 rounds
+        let estimate = edges as f64 * 40.0 * std::f64::consts::LN_2;
+        assert!((rounds_40 as f64 - estimate).abs() < 0.01 * estimate);
+        assert!(rounds_80 > rounds_40);
+        assert!(rounds_80.abs_diff(2 * rounds_40) <= 1);
+
+        // The bound is tight: one round fewer falls short
+        let miss = 1.0 - 1.0 / edges as f64;
+        assert!(miss.powf(rounds_40 as f64) <= 2f64.powi(-40));
+        assert!(miss.powf(rounds_40 as f64 - 1.0) > 2f64.powi(-40));
+
+        assert_eq!(ZKProtocol::rounds_for_soundness_bits(edges, 0), 0);
+        assert_eq!(ZKProtocol::rounds_for_soundness_bits(1, 128), 1);
+    }
+
+    #[test]
+    fn test_recommended_rounds() {
+        let protocol = ZKProtocol::new(&create_valid_input()).unwrap();
+        let edges = protocol.verifier.edge_map_len();
+        assert_eq!(
+            protocol.recommended_rounds(),
+            ZKProtocol::calculate_rounds_needed(edges, 99.99)
+        );
+
+        // A single clue makes a far harder puzzle, but only drops the other clues' edges
+        let solution = *create_valid_input().solution();
+        let mut sparse = SudokuGrid::new();
+        let point = crate::Point::default();
+        sparse.set_cell(point, solution.get_cell(point));
+        let harder = ZKProtocol::new(&ProofInput::new(&sparse, &solution).unwrap()).unwrap();
+        let harder_edges = harder.verifier.edge_map_len();
+        assert_eq!(
+            edges - harder_edges,
+            8 * (create_valid_input().puzzle().clue_count() - 1)
+        );
+        assert_eq!(
+            harder.recommended_rounds(),
+            ZKProtocol::calculate_rounds_needed(harder_edges, 99.99)
+        );
+        assert!(harder.recommended_rounds() < protocol.recommended_rounds());
+    }
+
+    #[test]
+    fn test_run_proof_cancellable() {
+        let mut protocol = ZKProtocol::new(&create_valid_input()).unwrap();
+        let cancel = AtomicBool::new(false);
+        assert_eq!(
+            protocol.run_proof_cancellable(10, &cancel).unwrap(),
+            ProofOutcome::Accepted
+        );
+
+        // Cancelling after those rounds stops the next proof before it runs any more
+        cancel.store(true, Ordering::Relaxed);
+        let outcome = protocol.run_proof_cancellable(10_000, &cancel).unwrap();
+        let ProofOutcome::Cancelled {
+            rounds_run,
+            confidence,
+        } = outcome
+        else {
+            panic!("expected a cancelled proof, got {outcome:?}");
+        };
+        assert_eq!(rounds_run, 10);
+        assert!(confidence > 0.0 && confidence < 99.0);
+        assert_eq!(confidence, protocol.confidence());
+
+        // Nothing to run is not an acceptance
+        cancel.store(false, Ordering::Relaxed);
+        assert!(matches!(
+            protocol.run_proof_cancellable(0, &cancel),
+            Err(ZkProofError::ZeroRounds)
+        ));
+    }
+
+    #[test]
+    fn test_run_proof_cancelled_mid_proof() {
+        let mut protocol = ZKProtocol::new(&create_valid_input()).unwrap();
+        let cancel = AtomicBool::new(false);
+        // Far more rounds than can run before the flag is flipped from another thread
+        let num_rounds = 1_000_000;
+        let started = Barrier::new(2);
+
+        let outcome = thread::scope(|scope| {
+            let proof = scope.spawn(|| {
+                started.wait();
+                protocol.run_proof_cancellable(num_rounds, &cancel)
+            });
+            started.wait();
+            thread::sleep(Duration::from_millis(100));
+            cancel.store(true, Ordering::Relaxed);
+            proof.join().unwrap().unwrap()
+        });
+
+        let ProofOutcome::Cancelled { rounds_run, .. } = outcome else {
+            panic!("expected a cancelled proof, got {outcome:?}");
+        };
+        assert!(rounds_run > 0 && rounds_run < num_rounds);
+        assert_eq!(rounds_run, protocol.rounds_run());
+    }
+
+    #[test]
+    fn test_builder() {
+        let input = create_valid_input();
+        let mut protocol = ZKProtocol::builder()
+            .confidence(50.0)
+            .seed(7)
+            .nonce_len(24)
+            .build(&input)
+            .unwrap();
+        assert!(protocol.run().unwrap());
+        let edge_count = protocol.verifier.edge_map_len();
+        assert_eq!(
+            protocol.verifier.metrics().rounds_total,
+            ZKProtocol::calculate_rounds_needed(edge_count, 50.0)
+        );
+
+        // The same seed gives the same shuffles
+        let mut other = ZKProtocol::builder().seed(7).build(&input).unwrap();
+        assert!(other.run_proof(1).unwrap());
+        let round = crate::RoundId(0);
+        assert_eq!(
+            protocol
+                .prover
+                .round_shuffle(round)
+                .unwrap()
+                .as_permutation_index(),
+            other
+                .prover
+                .round_shuffle(round)
+                .unwrap()
+                .as_permutation_index()
+        );
+
+        let mut exhaustive = ZKProtocol::builder()
+            .seed(3)
+            .challenge_strategy(ChallengeStrategy::Exhaustive)
+            .build(&input)
+            .unwrap();
+        assert!(exhaustive.run().unwrap());
+        assert_eq!(
+            exhaustive.verifier.metrics().unique_edges_challenged,
+            edge_count
+        );
+
+        assert!(matches!(
+            ZKProtocol::builder().nonce_len(8).build(&input),
+            Err(ZkProofError::InvalidReveal(
+                CommitmentError::NonceTooShort { len: 8, .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_puzzle_without_clues_rejected() {
+        let solution = create_valid_input().solution().to_owned();
+        let input = ProofInput::new(&SudokuGrid::new(), &solution).unwrap();
+        assert!(matches!(
+            ZKProtocol::new(&input),
+            Err(ZkProofError::SudokuError(msg)) if msg == "no clues to prove"
+        ));
+    }
+
+    #[test]
+    fn test_prove_with_zero_rounds_rejected() {
+        let mut protocol = ZKProtocol::new(&create_valid_input()).unwrap();
+
+        for confidence in [0.0, -5.0, f64::NAN] {
+            let edge_count = protocol.verifier.edge_map_len();
+            assert_eq!(
+                ZKProtocol::calculate_rounds_needed(edge_count, confidence),
+                0
+            );
+            assert!(matches!(
+                protocol.prove_with_confidence(confidence),
+                Err(ZkProofError::ZeroRounds)
+            ));
+        }
+    }
+}
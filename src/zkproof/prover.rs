@@ -1,57 +1,182 @@
 use petgraph::graph::{EdgeIndex, NodeIndex};
-use petgraph::visit::EdgeRef;
+use rand::{CryptoRng, RngCore};
 use std::collections::HashMap;
 
-use crate::{ColourShuffle, Commitment, CommitmentKey, Graph, SudokuGrid};
+use crate::{
+    Blake3Hasher, Cell, ColourShuffle, Commitment, CommitmentHasher, CommitmentKey,
+    DEFAULT_NONCE_LEN, Graph, Position, SudokuGrid,
+};
 
-use super::{EdgeNodeMap, NodeReveal, ZkProofError};
+use super::{EdgeNodeMap, NodeReveal, VerificationKey, ZkProofError};
 
 use super::types::{ProverCommitment, ProverResponse, RoundId, VerifierChallenge};
 
 pub struct ProverRound {
     commitment_keys: HashMap<NodeIndex, CommitmentKey>, // node_id -> commitment
     challenged_edges: Vec<EdgeIndex>,
+    colour_shuffle: ColourShuffle,
 }
 
 pub struct Prover {
     graph: Graph,
     rounds: Vec<ProverRound>,
     current_round: RoundId,
+    // Source of the colour shuffles, the thread RNG when `None`
+    shuffle_rng: Option<Box<dyn RngCore + Send>>,
+    nonce_len: usize,
 }
 
 impl Prover {
     pub fn new(puzzle: &SudokuGrid) -> Result<(Self, EdgeNodeMap), ZkProofError> {
+        // Contradictory hints are reported as such, before the grid as a whole is checked
+        let graph = Graph::try_from_sudoku(puzzle)
+            .map_err(|err| ZkProofError::GraphError(err.to_string()))?;
         // Validate the Sudoku puzzle
-        if !puzzle.is_valid_solution() {
-            return Err(ZkProofError::SudokuError(
-                "Invalid Sudoku puzzle".to_string(),
+        puzzle.validate()?;
+        Self::from_graph(graph)
+    }
+
+    /// Build a prover from a ready-made colouring graph, e.g. a variant such as X-Sudoku
+    /// with extra constraint edges. The graph's node values are the secret colouring.
+    /// Graphs with self-loops are rejected, as no colouring could ever pass such an edge.
+    pub fn from_graph(graph: Graph) -> Result<(Self, EdgeNodeMap), ZkProofError> {
+        if graph.has_self_loops() {
+            return Err(ZkProofError::GraphError(
+                "Graph contains a self-loop".to_string(),
             ));
         }
-        let graph = Graph::from_sudoku(puzzle);
-        let mut edge_map = HashMap::with_capacity(graph.graph.edge_count());
-        for edge_idx in graph.graph.edge_references() {
-            edge_map.insert(edge_idx.id(), (edge_idx.source(), edge_idx.target()));
-        }
-
+        let edge_map = graph.edge_map();
         Ok((
             Self {
                 graph,
                 rounds: Vec::with_capacity(128),
                 current_round: RoundId(0),
+                shuffle_rng: None,
+                nonce_len: DEFAULT_NONCE_LEN,
             },
             edge_map,
         ))
     }
 
+    /// Like [`Prover::new`], but drawing every round's colour shuffle from `rng`.
+    ///
+    /// Soundness relies on each round's shuffle being independent of the others: if the
+    /// verifier could predict or correlate shuffles, revealed colours from earlier rounds
+    /// would leak the colouring and let a cheater know which edges are safe. Passing a
+    /// CSPRNG that is periodically reseeded from the OS, such as
+    /// `ReseedingRng<ChaCha20Core, OsRng>`, keeps the shuffles independent even if the
+    /// thread RNG's entropy is in doubt.
+    pub fn new_with_rng<R: CryptoRng + Send + 'static>(
+        puzzle: &SudokuGrid,
+        rng: R,
+    ) -> Result<(Self, EdgeNodeMap), ZkProofError> {
+        let (mut prover, edge_map) = Self::new(puzzle)?;
+        prover.shuffle_rng = Some(Box::new(rng));
+        Ok((prover, edge_map))
+    }
+
+    /// Strict constructor for a public puzzle, which may only contain hints and empties.
+    /// Any `Cell::Guess` is rejected; the secret solution is derived by solving the puzzle.
+    pub fn new_strict(puzzle: &SudokuGrid) -> Result<(Self, EdgeNodeMap), ZkProofError> {
+        if Position::all_board_positions().any(|point| puzzle.get_cell(point).is_guess()) {
+            return Err(ZkProofError::SudokuError(
+                "Puzzle must only contain hints and empty cells".to_string(),
+            ));
+        }
+        let mut solution = *puzzle;
+        solution
+            .fill_remaining()
+            .map_err(|e| ZkProofError::SudokuError(e.to_string()))?;
+        Self::new(&solution)
+    }
+
+    /// Use `len` byte nonces for commitments in rounds started from now on
+    pub fn set_nonce_len(&mut self, len: usize) {
+        self.nonce_len = len;
+    }
+
+    /// The secret solution this prover proves knowledge of, read back from the graph's cell
+    /// nodes. Cells anchored to the clique nodes come back as hints, the rest as guesses.
+    ///
+    /// Only for trusted local use such as tests and tooling: this is exactly what the proof
+    /// keeps hidden, so it must never be sent to a verifier.
+    pub fn solution(&self) -> SudokuGrid {
+        let mut grid = SudokuGrid::new();
+        for (node, value) in self.graph.nodes() {
+            let Some(point) = self.graph.node_point(node) else {
+                continue;
+            };
+            let anchored = self
+                .graph
+                .graph
+                .neighbors(node)
+                .any(|neighbour| self.graph.node_point(neighbour).is_none());
+            let cell = if anchored {
+                Cell::Hint(value)
+            } else {
+                Cell::Guess(value)
+            };
+            grid.set_cell(point, cell);
+        }
+        grid
+    }
+
+    /// The public half of the prover's setup, which can be handed to a verifier
+    pub fn verification_key(&self) -> VerificationKey {
+        VerificationKey::from_graph(&self.graph)
+    }
+
+    /// Number of edges the prover can answer challenges for
+    pub fn edge_count(&self) -> usize {
+        self.graph.edge_count()
+    }
+
+    /// All edges the prover can answer challenges for, e.g. to drive an exhaustive proof
+    pub fn edges(&self) -> impl Iterator<Item = EdgeIndex> {
+        self.graph.edges()
+    }
+
+    /// The colour shuffle used for a round, so the independence of rounds can be audited.
+    /// Returns `None` for rounds that haven't been started.
+    pub fn round_shuffle(&self, round: RoundId) -> Option<&ColourShuffle> {
+        self.rounds.get(round.0).map(|round| &round.colour_shuffle)
+    }
+
+    /// Rebuild the commitment sent for a past round, e.g. to retransmit it after a loss.
+    /// The hashes are recomputed from the round's kept keys, so they match the original.
+    /// Returns `None` for rounds that haven't been started.
+    pub fn commitment_for_round(&self, round: RoundId) -> Option<ProverCommitment> {
+        let commitments = self
+            .rounds
+            .get(round.0)?
+            .commitment_keys
+            .iter()
+            .map(|(node, key)| {
+                let hash = Blake3Hasher.hash(key.value(), *node, key.nonce());
+                (*node, Commitment::from_parts(hash, node.index()))
+            })
+            .collect();
+        Some(ProverCommitment {
+            round_id: round,
+            commitments,
+        })
+    }
+
     pub fn start_round(&mut self) -> ProverCommitment {
-        let colour_shuffle = ColourShuffle::new_random();
+        let colour_shuffle = match &mut self.shuffle_rng {
+            Some(rng) => ColourShuffle::new_with_rng(rng),
+            None => ColourShuffle::new_random(),
+        };
 
         let (node_commitments, commitment_keys): (HashMap<_, _>, HashMap<_, _>) = self
             .graph
             .nodes()
             .map(|(node_id, value)| {
-                let (commitment, key) =
-                    Commitment::new(colour_shuffle.apply(value), node_id.index());
+                let (commitment, key) = Commitment::new_with_nonce_len(
+                    colour_shuffle.apply(value),
+                    node_id,
+                    self.nonce_len,
+                );
                 ((node_id, commitment), (node_id, key))
             })
             .unzip();
@@ -59,6 +184,7 @@ impl Prover {
         let round = ProverRound {
             commitment_keys,
             challenged_edges: Vec::new(),
+            colour_shuffle,
         };
 
         let round_id = RoundId(self.rounds.len());
@@ -70,16 +196,16 @@ impl Prover {
         }
     }
 
+    /// Answer a challenge for any round started so far, not only the current one, as each
+    /// round's commitment keys are kept. This allows batched challenges for past rounds.
     pub fn respond_to_challenge(
         &mut self,
         challenge: VerifierChallenge,
     ) -> Result<ProverResponse, ZkProofError> {
-        if challenge.round_id != self.current_round {
-            return Err(ZkProofError::RoundMismatch);
-        }
-
-        let round_idx = challenge.round_id.0;
-        let round = &mut self.rounds[round_idx];
+        let round = self
+            .rounds
+            .get_mut(challenge.round_id.0)
+            .ok_or(ZkProofError::RoundMismatch)?;
 
         if round.challenged_edges.contains(&challenge.edge) {
             return Err(ZkProofError::AlreadyRevealed);
@@ -116,20 +242,29 @@ impl Prover {
             },
         })
     }
+
+    /// Answer several challenges issued against the same commitment
+    pub fn respond_to_challenges(
+        &mut self,
+        challenges: &[VerifierChallenge],
+    ) -> Result<Vec<ProverResponse>, ZkProofError> {
+        challenges
+            .iter()
+            .map(|challenge| self.respond_to_challenge(*challenge))
+            .collect()
+    }
 }
 
 #[cfg(test)]
 mod test {
     use std::str::FromStr;
 
-    use super::*;
+    use rand::rngs::{OsRng, ReseedingRng};
+    use rand_chacha::ChaCha20Core;
 
-    fn create_valid_sudoku() -> SudokuGrid {
-        SudokuGrid::from_str(
-            "296541378851273694743698251915764832387152946624839517139486725478325169562917483",
-        )
-        .unwrap()
-    }
+    use crate::{Cell, Point, Value};
+
+    use super::*;
 
     fn create_invalid_sudoku() -> SudokuGrid {
         SudokuGrid::from_str(
@@ -140,7 +275,7 @@ mod test {
 
     #[test]
     fn test_prover_creation_valid_sudoku() {
-        let grid = create_valid_sudoku();
+        let grid = SudokuGrid::test_solution();
         let result = Prover::new(&grid);
         assert!(result.is_ok());
 
@@ -158,9 +293,121 @@ mod test {
         assert!(matches!(result, Err(ZkProofError::SudokuError(_))));
     }
 
+    #[test]
+    fn test_invalid_sudoku_error_names_unit() {
+        // The last cell is a 2 instead of a 3, repeating the 2 earlier in row nine
+        let Err(err) = Prover::new(&create_invalid_sudoku()) else {
+            panic!("invalid grid accepted");
+        };
+        let message = err.to_string();
+        assert!(message.contains("row 9 repeats 2"), "{message}");
+        assert!(message.contains("column 9 repeats 2"), "{message}");
+    }
+
+    #[test]
+    fn test_prover_rejects_contradictory_hints() {
+        // A full grid whose first row holds two hinted 2s
+        let mut grid = SudokuGrid::test_solution();
+        grid.set_cell(Point::new(Position::ONE, Position::ONE), Cell::new_hint(2));
+        grid.set_cell(Point::new(Position::ONE, Position::TWO), Cell::new_hint(2));
+
+        let result = Prover::new(&grid);
+        assert!(
+            matches!(result, Err(ZkProofError::GraphError(msg)) if msg == "Hints contradict each other")
+        );
+    }
+
+    #[test]
+    fn test_prover_strict_rejects_guesses() {
+        // Parsed grids are made of guesses, so only the lenient constructor accepts them
+        let grid = SudokuGrid::test_solution();
+        assert!(Prover::new(&grid).is_ok());
+        assert!(matches!(
+            Prover::new_strict(&grid),
+            Err(ZkProofError::SudokuError(_))
+        ));
+
+        // The same grid as a hint-only puzzle with some cells removed is accepted
+        let mut puzzle = SudokuGrid::new();
+        for (i, point) in Position::all_board_positions().enumerate() {
+            if i % 2 == 0 {
+                let value = grid.get_cell(point).value().unwrap();
+                puzzle.set_cell(point, Cell::Hint(value));
+            }
+        }
+        assert!(Prover::new_strict(&puzzle).is_ok());
+    }
+
+    #[test]
+    fn test_prover_edges_match_edge_map() {
+        let grid = SudokuGrid::test_solution();
+        let (prover, edge_map) = Prover::new(&grid).unwrap();
+
+        assert_eq!(prover.edge_count(), edge_map.len());
+        let edges: Vec<EdgeIndex> = prover.edges().collect();
+        assert_eq!(edges.len(), edge_map.len());
+        assert!(edges.iter().all(|edge| edge_map.contains_key(edge)));
+    }
+
+    #[test]
+    fn test_prover_from_variant_graph() {
+        // A solution that also satisfies the X-Sudoku diagonal constraints
+        let grid = SudokuGrid::from_str(
+            "215438679369271845478695321824967153596312784731854962182743596957126438643589217",
+        )
+        .unwrap();
+        let mut graph = Graph::from_sudoku(&grid);
+        let base_edges = graph.edge_count();
+
+        // Cell nodes are added row by row, so the node for (x, y) is x * 9 + y
+        for i in 0..9 {
+            for j in (i + 1)..9 {
+                graph
+                    .graph
+                    .add_edge(NodeIndex::new(i * 9 + i), NodeIndex::new(j * 9 + j), ());
+                graph.graph.add_edge(
+                    NodeIndex::new(i * 9 + (8 - i)),
+                    NodeIndex::new(j * 9 + (8 - j)),
+                    (),
+                );
+            }
+        }
+
+        let (mut prover, edge_map) = Prover::from_graph(graph).unwrap();
+        assert_eq!(edge_map.len(), base_edges + 72);
+
+        // Every edge, including the diagonal ones, can be answered with distinct colours
+        let commitment = prover.start_round();
+        for edge in edge_map.keys() {
+            let challenge = VerifierChallenge {
+                round_id: commitment.round_id,
+                edge: *edge,
+            };
+            let response = prover.respond_to_challenge(challenge).unwrap();
+            assert_ne!(
+                response.node1.node_key.value(),
+                response.node2.node_key.value()
+            );
+        }
+    }
+
+    #[test]
+    fn test_prover_rejects_self_loops() {
+        let mut graph = Graph::from_sudoku(&SudokuGrid::test_solution());
+        assert!(!graph.has_self_loops());
+
+        let node = NodeIndex::new(40);
+        graph.graph.add_edge(node, node, ());
+        assert!(graph.has_self_loops());
+        assert!(matches!(
+            Prover::from_graph(graph),
+            Err(ZkProofError::GraphError(_))
+        ));
+    }
+
     #[test]
     fn test_start_round() {
-        let grid = create_valid_sudoku();
+        let grid = SudokuGrid::test_solution();
         let (mut prover, _) = Prover::new(&grid).unwrap();
 
         // Start a round
@@ -175,7 +422,7 @@ mod test {
 
     #[test]
     fn test_multiple_rounds() {
-        let grid = create_valid_sudoku();
+        let grid = SudokuGrid::test_solution();
         let (mut prover, _) = Prover::new(&grid).unwrap();
 
         // Start first round
@@ -202,9 +449,91 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_solution() {
+        let grid = SudokuGrid::test_solution();
+        let (prover, _) = Prover::new(&grid).unwrap();
+        assert_eq!(prover.solution(), grid);
+
+        let mut with_hints = grid;
+        for (i, point) in Position::all_board_positions().enumerate() {
+            if i % 3 == 0 {
+                with_hints.set_cell(point, Cell::Hint(grid.get_cell(point).value().unwrap()));
+            }
+        }
+        let (prover, _) = Prover::new(&with_hints).unwrap();
+        assert_eq!(prover.solution(), with_hints);
+    }
+
+    #[test]
+    fn test_commitment_for_round() {
+        let (mut prover, _) = Prover::new(&SudokuGrid::test_solution()).unwrap();
+        assert!(prover.commitment_for_round(RoundId(0)).is_none());
+
+        let original = prover.start_round();
+        for _ in 0..5 {
+            prover.start_round();
+        }
+
+        let refetched = prover.commitment_for_round(RoundId(0)).unwrap();
+        assert_eq!(refetched.round_id, original.round_id);
+        assert_eq!(refetched.digest(), original.digest());
+        for (node, commitment) in &original.commitments {
+            assert_eq!(refetched.commitments[node].hash(), commitment.hash());
+            assert_eq!(refetched.commitments[node].node_id(), commitment.node_id());
+        }
+        assert!(prover.commitment_for_round(RoundId(6)).is_none());
+    }
+
+    #[test]
+    fn test_round_shuffles_independent() {
+        let grid = SudokuGrid::test_solution();
+        let (mut prover, _) = Prover::new(&grid).unwrap();
+        assert!(prover.round_shuffle(RoundId(0)).is_none());
+
+        for _ in 0..10 {
+            prover.start_round();
+        }
+        let indices: Vec<u32> = (0..10)
+            .map(|i| {
+                prover
+                    .round_shuffle(RoundId(i))
+                    .unwrap()
+                    .as_permutation_index()
+            })
+            .collect();
+        for pair in indices.windows(2) {
+            assert_ne!(pair[0], pair[1], "consecutive rounds reused a shuffle");
+        }
+        assert!(prover.round_shuffle(RoundId(10)).is_none());
+    }
+
+    #[test]
+    fn test_new_with_rng_uniform_shuffles() {
+        let rng = ReseedingRng::<ChaCha20Core, OsRng>::new(1 << 16, OsRng).unwrap();
+        let (mut prover, _) = Prover::new_with_rng(&SudokuGrid::test_solution(), rng).unwrap();
+
+        // Count where the shuffles send a One; each of the nine colours should be equally likely
+        let rounds = 1_800;
+        let mut counts = [0usize; 9];
+        for i in 0..rounds {
+            prover.start_round();
+            let shuffle = prover.round_shuffle(RoundId(i)).unwrap();
+            counts[shuffle.apply(Value::One).to_index()] += 1;
+        }
+
+        let expected = rounds as f64 / 9.0;
+        let chi_square: f64 = counts
+            .iter()
+            .map(|&count| (count as f64 - expected).powi(2) / expected)
+            .sum();
+        // 8 degrees of freedom: p < 0.00001 beyond ~37
+        assert!(chi_square < 40.0, "shuffles look skewed: {counts:?}");
+    }
+
     #[test]
     fn test_respond_to_challenge() {
-        let grid = create_valid_sudoku();
+        let grid = SudokuGrid::test_solution();
         let (mut prover, edge_map) = Prover::new(&grid).unwrap();
 
         // Start a round
@@ -236,31 +565,54 @@ mod test {
     }
 
     #[test]
-    fn test_challenge_wrong_round() {
-        let grid = create_valid_sudoku();
+    fn test_challenge_past_round() {
+        let grid = SudokuGrid::test_solution();
         let (mut prover, edge_map) = Prover::new(&grid).unwrap();
 
-        // Start round 0
-        prover.start_round();
-
-        // Start round 1
-        prover.start_round();
+        // Start rounds 0 to 3, keeping round 0's commitment
+        let first = prover.start_round();
+        for _ in 0..3 {
+            prover.start_round();
+        }
 
-        // Create challenge for round 0 while round 1 is current
+        // Challenge round 0 while round 3 is current
         let edge = *edge_map.keys().next().unwrap();
         let challenge = VerifierChallenge {
             round_id: RoundId(0),
             edge,
         };
+        let response = prover.respond_to_challenge(challenge).unwrap();
+        assert_eq!(response.round_id, RoundId(0));
+        let (node1, node2) = edge_map[&edge];
+        assert_eq!(response.nodes(), (node1, node2));
+        for reveal in [&response.node1, &response.node2] {
+            assert!(
+                first.commitments[&reveal.node_idx]
+                    .verify(&reveal.node_key)
+                    .is_ok()
+            );
+        }
 
-        // Should fail due to round mismatch
-        let response = prover.respond_to_challenge(challenge);
-        assert!(matches!(response, Err(ZkProofError::RoundMismatch)));
+        // Still only once per edge
+        assert!(matches!(
+            prover.respond_to_challenge(challenge),
+            Err(ZkProofError::AlreadyRevealed)
+        ));
+
+        // Rounds that were never started can't be answered
+        let future = VerifierChallenge {
+            round_id: RoundId(4),
+            edge,
+        };
+        assert!(matches!(
+            prover.respond_to_challenge(future),
+            Err(ZkProofError::RoundMismatch)
+        ));
     }
 
     #[test]
     fn test_challenge_same_edge_twice() {
-        let grid = create_valid_sudoku();
+        let grid = SudokuGrid::test_solution();
         let (mut prover, edge_map) = Prover::new(&grid).unwrap();
 
         // Start a round
@@ -283,7 +635,7 @@ mod test {
 
     #[test]
     fn test_challenge_nonexistent_edge() {
-        let grid = create_valid_sudoku();
+        let grid = SudokuGrid::test_solution();
         let (mut prover, edge_map) = Prover::new(&grid).unwrap();
 
         // Start a round
@@ -305,7 +657,7 @@ mod test {
 
     #[test]
     fn test_revealed_values_valid_for_edge() {
-        let grid = create_valid_sudoku();
+        let grid = SudokuGrid::test_solution();
         let (mut prover, edge_map) = Prover::new(&grid).unwrap();
 
         // Start a round
@@ -0,0 +1,221 @@
+use std::collections::HashMap;
+
+use bytes::Bytes;
+use petgraph::graph::{EdgeIndex, NodeIndex};
+
+use super::{NodeReveal, ProverResponse, RoundId, ZkProofError};
+
+/// One round as recorded in a transcript, linked to the round before it by `prev_hash`
+#[derive(Debug, Clone)]
+pub struct TranscriptRound {
+    pub round_id: RoundId,
+    pub prev_hash: Bytes,
+    pub commitment_digest: Bytes,
+    pub challenges: Vec<EdgeIndex>,
+    pub responses: Vec<ProverResponse>,
+}
+
+impl TranscriptRound {
+    /// The chain hash of this round, covering its contents and the previous link
+    pub fn link_hash(&self) -> Bytes {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(&self.prev_hash);
+        hasher.update(&(self.round_id.0 as u64).to_le_bytes());
+        hasher.update(&self.commitment_digest);
+        for edge in &self.challenges {
+            hasher.update(&(edge.index() as u64).to_le_bytes());
+        }
+        for response in &self.responses {
+            hasher.update(&(response.edge.index() as u64).to_le_bytes());
+            for NodeReveal { node_idx, node_key } in [&response.node1, &response.node2] {
+                hasher.update(&(node_idx.index() as u64).to_le_bytes());
+                hasher.update(&[node_key.value().to_numeric()]);
+                hasher.update(&(node_key.nonce().len() as u64).to_le_bytes());
+                hasher.update(node_key.nonce());
+            }
+        }
+        Bytes::copy_from_slice(hasher.finalize().as_bytes())
+    }
+}
+
+/// A serialisable record of a proof session. Each round carries the hash of the one before
+/// it and `final_hash` covers the whole chain, so rounds can't be reordered, dropped or
+/// spliced in without `verify_chain` noticing.
+#[derive(Debug, Clone)]
+pub struct ProofTranscript {
+    pub rounds: Vec<TranscriptRound>,
+    pub final_hash: Bytes,
+}
+
+impl ProofTranscript {
+    /// The `prev_hash` of the first round
+    pub const GENESIS_HASH: [u8; 32] = [0; 32];
+
+    pub fn new() -> Self {
+        Self {
+            rounds: Vec::new(),
+            final_hash: Bytes::from_static(&Self::GENESIS_HASH),
+        }
+    }
+
+    /// Append a round, linking it to the current end of the chain
+    pub fn push_round(
+        &mut self,
+        round_id: RoundId,
+        commitment_digest: Bytes,
+        challenges: Vec<EdgeIndex>,
+        responses: Vec<ProverResponse>,
+    ) {
+        let round = TranscriptRound {
+            round_id,
+            prev_hash: self.final_hash.clone(),
+            commitment_digest,
+            challenges,
+            responses,
+        };
+        self.final_hash = round.link_hash();
+        self.rounds.push(round);
+    }
+
+    /// Recompute the hash chain, rejecting a transcript whose rounds were reordered or altered
+    pub fn verify_chain(&self) -> Result<(), ZkProofError> {
+        let mut prev_hash = Bytes::from_static(&Self::GENESIS_HASH);
+        for (i, round) in self.rounds.iter().enumerate() {
+            if round.round_id != RoundId(i) || round.prev_hash != prev_hash {
+                return Err(ZkProofError::TranscriptMismatch(i));
+            }
+            prev_hash = round.link_hash();
+        }
+        if prev_hash != self.final_hash {
+            return Err(ZkProofError::TranscriptMismatch(self.rounds.len()));
+        }
+        Ok(())
+    }
+
+    /// Every node revealed with the same nonce in two different rounds, as
+    /// `(node, first round, repeating round)`. Reused nonces let an observer link a node's
+    /// commitments across rounds, so an honest prover never produces any.
+    pub fn detect_nonce_reuse(&self) -> Vec<(NodeIndex, RoundId, RoundId)> {
+        let mut first_seen: HashMap<(NodeIndex, &[u8]), RoundId> = HashMap::new();
+        let mut reuses = Vec::new();
+        for round in &self.rounds {
+            let reveals = round
+                .responses
+                .iter()
+                .flat_map(|response| [&response.node1, &response.node2]);
+            for NodeReveal { node_idx, node_key } in reveals {
+                let first = *first_seen
+                    .entry((*node_idx, node_key.nonce()))
+                    .or_insert(round.round_id);
+                // Several challenged edges can reveal the same node within one round
+                if first != round.round_id && !reuses.contains(&(*node_idx, first, round.round_id))
+                {
+                    reuses.push((*node_idx, first, round.round_id));
+                }
+            }
+        }
+        reuses
+    }
+}
+
+impl Default for ProofTranscript {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{CommitmentKey, Prover, SudokuGrid, Verifier};
+
+    use super::*;
+
+    fn run_transcript(rounds: usize) -> ProofTranscript {
+        let grid = SudokuGrid::test_solution();
+        let (mut prover, edge_map) = Prover::new(&grid).unwrap();
+        let mut verifier = Verifier::new(edge_map);
+        for _ in 0..rounds {
+            let challenge = verifier.receive_commitment(prover.start_round()).unwrap();
+            let response = prover.respond_to_challenge(challenge).unwrap();
+            verifier.verify_response(response).unwrap();
+        }
+        verifier.transcript()
+    }
+
+    #[test]
+    fn test_transcript_chain_valid() {
+        let transcript = run_transcript(5);
+        assert_eq!(transcript.rounds.len(), 5);
+        assert_eq!(
+            transcript.rounds[0].prev_hash,
+            &ProofTranscript::GENESIS_HASH[..]
+        );
+        for pair in transcript.rounds.windows(2) {
+            assert_eq!(pair[1].prev_hash, pair[0].link_hash());
+        }
+        assert!(transcript.verify_chain().is_ok());
+    }
+
+    #[test]
+    fn test_transcript_reordering_detected() {
+        let mut transcript = run_transcript(5);
+        transcript.rounds.swap(1, 2);
+        assert!(matches!(
+            transcript.verify_chain(),
+            Err(ZkProofError::TranscriptMismatch(1))
+        ));
+
+        // Relabelling the swapped rounds' ids doesn't hide the broken links
+        let mut transcript = run_transcript(5);
+        transcript.rounds.swap(1, 2);
+        transcript.rounds[1].round_id = RoundId(1);
+        transcript.rounds[2].round_id = RoundId(2);
+        assert!(transcript.verify_chain().is_err());
+    }
+
+    #[test]
+    fn test_detect_nonce_reuse() {
+        assert!(run_transcript(20).detect_nonce_reuse().is_empty());
+
+        // Challenge the same edge twice, then have the second round reuse the first's nonce
+        let grid = SudokuGrid::test_solution();
+        let (mut prover, edge_map) = Prover::new(&grid).unwrap();
+        let mut verifier = Verifier::new(edge_map);
+        let edge = EdgeIndex::new(0);
+        let mut responses = Vec::new();
+        for _ in 0..2 {
+            let challenge = verifier
+                .receive_commitment_for_edge(prover.start_round(), edge)
+                .unwrap();
+            let response = prover.respond_to_challenge(challenge).unwrap();
+            responses.push(response.clone());
+            verifier.verify_response(response).unwrap();
+        }
+        let mut transcript = verifier.transcript();
+        let reused = &responses[0].node1;
+        let second = &mut transcript.rounds[1].responses[0].node1;
+        second.node_key = CommitmentKey::new(
+            second.node_key.value(),
+            Bytes::copy_from_slice(reused.node_key.nonce()),
+        );
+
+        assert_eq!(
+            transcript.detect_nonce_reuse(),
+            vec![(reused.node_idx, RoundId(0), RoundId(1))]
+        );
+    }
+
+    #[test]
+    fn test_transcript_tampering_detected() {
+        let mut transcript = run_transcript(3);
+        transcript.rounds[2].challenges.push(EdgeIndex::new(0));
+        assert!(matches!(
+            transcript.verify_chain(),
+            Err(ZkProofError::TranscriptMismatch(3))
+        ));
+
+        let mut transcript = run_transcript(3);
+        transcript.rounds.pop();
+        assert!(transcript.verify_chain().is_err());
+    }
+}
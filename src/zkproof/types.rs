@@ -1,8 +1,9 @@
 // src/zkproof/types.rs
 use crate::{
-    CommitmentError,
+    CommitmentError, Conflict, Value,
     crypto::{Commitment, CommitmentKey, Hidden},
 };
+use bytes::Bytes;
 use petgraph::graph::{EdgeIndex, NodeIndex};
 use std::collections::HashMap;
 // Round identifier with newtype pattern for type safety
@@ -11,23 +12,76 @@ pub struct RoundId(pub usize);
 
 pub type EdgeNodeMap = HashMap<EdgeIndex, (NodeIndex, NodeIndex)>;
 
+/// Blake3 root over every `edge -> (node, node)` entry in `EdgeIndex` order.
+/// Publishing it ahead of a proof pins down which edge map the verifier must use.
+pub fn edge_map_root(map: &EdgeNodeMap) -> Bytes {
+    let mut entries: Vec<_> = map.iter().collect();
+    entries.sort_unstable_by_key(|(edge, _)| **edge);
+
+    let mut hasher = blake3::Hasher::new();
+    for (edge, (node1, node2)) in entries {
+        hasher.update(&(edge.index() as u64).to_le_bytes());
+        hasher.update(&(node1.index() as u64).to_le_bytes());
+        hasher.update(&(node2.index() as u64).to_le_bytes());
+    }
+    Bytes::copy_from_slice(hasher.finalize().as_bytes())
+}
+
 #[derive(Debug, Clone)]
 pub struct ProverCommitment {
     pub round_id: RoundId,
     pub commitments: HashMap<NodeIndex, Commitment<Hidden>>,
 }
 
+impl ProverCommitment {
+    /// Blake3 digest over the round id and every node's commitment hash in `NodeIndex` order.
+    /// Lets the receiver detect a commitment corrupted in transit.
+    pub fn digest(&self) -> Bytes {
+        let mut nodes: Vec<_> = self.commitments.iter().collect();
+        nodes.sort_unstable_by_key(|(node, _)| **node);
+
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(&(self.round_id.0 as u64).to_le_bytes());
+        for (node, commitment) in nodes {
+            hasher.update(&(node.index() as u64).to_le_bytes());
+            hasher.update(commitment.hash().as_bytes());
+        }
+        Bytes::copy_from_slice(hasher.finalize().as_bytes())
+    }
+
+    /// Check the commitment against a digest sent alongside it, before processing it
+    pub fn verify_digest(&self, expected: &[u8]) -> Result<(), ZkProofError> {
+        if self.digest() == expected {
+            Ok(())
+        } else {
+            Err(ZkProofError::DigestMismatch)
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct VerifierChallenge {
     pub round_id: RoundId,
     pub edge: EdgeIndex,
 }
 
+/// One opened commitment. The value is the node's colour after the round's colour shuffle,
+/// not its digit in the solution: it is enough to check that an edge's ends differ, but the
+/// verifier can't undo the shuffle to recover the solution.
+#[derive(Debug, Clone)]
 pub struct NodeReveal {
     pub node_idx: NodeIndex,
     pub node_key: CommitmentKey,
 }
 
+impl NodeReveal {
+    /// The revealed shuffled colour. Being a [`Value`], it is always in 1-9.
+    pub fn value(&self) -> Value {
+        self.node_key.value()
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct ProverResponse {
     pub round_id: RoundId,
     pub edge: EdgeIndex,
@@ -35,12 +89,49 @@ pub struct ProverResponse {
     pub node2: NodeReveal,
 }
 
+impl ProverResponse {
+    /// The two revealed colours, in `node1`, `node2` order
+    pub fn revealed_values(&self) -> (Value, Value) {
+        (self.node1.value(), self.node2.value())
+    }
+
+    /// The two revealed nodes, in `node1`, `node2` order
+    pub fn nodes(&self) -> (NodeIndex, NodeIndex) {
+        (self.node1.node_idx, self.node2.node_idx)
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct VerifierResult {
     pub round_id: RoundId,
     pub success: bool,
 }
 
+/// The verifier's overall decision on a session so far
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    /// At least one round passed and failures are within tolerance
+    Accepted,
+    /// More rounds failed than the verifier tolerates
+    Rejected,
+    /// No round has passed yet, or a challenge is still unanswered
+    Pending,
+}
+
+/// Plain counters describing a verifier's session, suitable for emitting as metrics
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Metrics {
+    pub rounds_total: usize,
+    pub rounds_passed: usize,
+    /// Answered rounds that failed, whether by a colour clash or a reveal that didn't open
+    pub rounds_failed: usize,
+    /// Rounds still awaiting a response, at most the current one
+    pub rounds_pending: usize,
+    pub edges_total: usize,
+    pub unique_edges_challenged: usize,
+    pub confidence_bp: u32,
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum ZkProofError {
     #[error("Node not found: {0}")]
@@ -53,10 +144,34 @@ pub enum ZkProofError {
     InvalidReveal(#[from] CommitmentError),
     #[error("No edges available")]
     NoEdges,
+    #[error("Invalid number of edges to challenge: {0}")]
+    InvalidChallengeCount(usize),
+    #[error("Requested confidence requires zero rounds, so nothing would be proven")]
+    ZeroRounds,
+    #[error("Invalid confidence: {0}, must be a finite percentage below 100")]
+    InvalidConfidence(f64),
     #[error("Round mismatch")]
     RoundMismatch,
+    #[error("Challenge for round {0} was never answered")]
+    UnansweredChallenge(usize),
+    #[error("Commitment digest mismatch")]
+    DigestMismatch,
+    #[error("Edge map root mismatch")]
+    EdgeMapRootMismatch,
+    #[error("Malformed verification key")]
+    InvalidVerificationKey,
+    #[error("Transcript hash chain mismatch at round {0}")]
+    TranscriptMismatch(usize),
+    #[error("Unknown puzzle: {0}")]
+    UnknownPuzzle(usize),
+    #[error("Unexpected protocol message")]
+    UnexpectedMessage,
+    #[error("Timed out waiting for the peer's next message")]
+    Timeout,
     #[error("Commitment already revealed")]
     AlreadyRevealed,
+    #[error("Round already failed")]
+    RoundFailed,
     #[error("Value not found for node")]
     ValueNotFound,
     #[error("Graph error: {0}")]
@@ -64,3 +179,111 @@ pub enum ZkProofError {
     #[error("Sudoku error: {0}")]
     SudokuError(String),
 }
+
+impl From<Vec<Conflict>> for ZkProofError {
+    /// An invalid grid, naming every unit that repeats a value
+    fn from(conflicts: Vec<Conflict>) -> Self {
+        let details = conflicts
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        ZkProofError::SudokuError(format!("Invalid Sudoku puzzle: {}", details.join(", ")))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Point, Position, Prover, SudokuGrid};
+
+    use super::*;
+
+    fn create_commitment() -> ProverCommitment {
+        let grid = SudokuGrid::test_solution();
+        let (mut prover, _) = Prover::new(&grid).unwrap();
+        prover.start_round()
+    }
+
+    #[test]
+    fn test_commitment_digest() {
+        let commitment = create_commitment();
+        let digest = commitment.digest();
+
+        // Deterministic regardless of map iteration order
+        assert_eq!(digest, commitment.clone().digest());
+        assert!(commitment.verify_digest(&digest).is_ok());
+
+        // Flip one byte of one commitment hash
+        let mut corrupted = commitment.clone();
+        let node = NodeIndex::new(0);
+        let mut hash = corrupted.commitments[&node].hash().as_bytes().to_vec();
+        hash[0] ^= 0xff;
+        corrupted
+            .commitments
+            .insert(node, Commitment::from_parts(hash.into(), node.index()));
+
+        assert_ne!(corrupted.digest(), digest);
+        assert!(matches!(
+            corrupted.verify_digest(&digest),
+            Err(ZkProofError::DigestMismatch)
+        ));
+
+        // The round id is covered too
+        let renumbered = ProverCommitment {
+            round_id: RoundId(1),
+            ..commitment
+        };
+        assert_ne!(renumbered.digest(), digest);
+    }
+
+    #[test]
+    fn test_revealed_values_are_shuffled_colours() {
+        let grid = SudokuGrid::test_solution();
+        let (mut prover, _) = Prover::new(&grid).unwrap();
+        // Cell nodes are numbered row by row
+        let digit = |node: NodeIndex| {
+            let point = Point::new(
+                Position::from_index(node.index() / 9),
+                Position::from_index(node.index() % 9),
+            );
+            grid.get_cell(point).value().unwrap()
+        };
+
+        for i in 0..50 {
+            let round_id = prover.start_round().round_id;
+            let challenge = VerifierChallenge {
+                round_id,
+                edge: EdgeIndex::new(i * 7),
+            };
+            let response = prover.respond_to_challenge(challenge).unwrap();
+            let shuffle = prover.round_shuffle(round_id).unwrap();
+            for reveal in [&response.node1, &response.node2] {
+                assert!((1..=9).contains(&reveal.value().to_numeric()));
+                assert_eq!(reveal.value(), shuffle.apply(digit(reveal.node_idx)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_response_accessors() {
+        let grid = SudokuGrid::test_solution();
+        let (mut prover, _) = Prover::new(&grid).unwrap();
+        let commitment = prover.start_round();
+        let challenge = VerifierChallenge {
+            round_id: commitment.round_id,
+            edge: EdgeIndex::new(0),
+        };
+        let response = prover.respond_to_challenge(challenge).unwrap();
+
+        assert_eq!(
+            response.revealed_values(),
+            (
+                response.node1.node_key.value(),
+                response.node2.node_key.value()
+            )
+        );
+        assert_eq!(
+            response.nodes(),
+            (response.node1.node_idx, response.node2.node_idx)
+        );
+    }
+}
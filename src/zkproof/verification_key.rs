@@ -0,0 +1,135 @@
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use petgraph::graph::{EdgeIndex, NodeIndex};
+
+use crate::Graph;
+
+use super::{EdgeNodeMap, ZkProofError, edge_map_root};
+
+/// Everything a verifier needs about the graph, and nothing about the prover's colouring:
+/// the edge map, the number of nodes and the root committing to the edge map.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerificationKey {
+    edge_map: EdgeNodeMap,
+    node_count: usize,
+    root: Bytes,
+}
+
+impl VerificationKey {
+    pub fn new(edge_map: EdgeNodeMap, node_count: usize) -> Self {
+        let root = edge_map_root(&edge_map);
+        Self {
+            edge_map,
+            node_count,
+            root,
+        }
+    }
+
+    pub fn from_graph(graph: &Graph) -> Self {
+        Self::new(graph.edge_map(), graph.node_count())
+    }
+
+    pub fn edge_map(&self) -> &EdgeNodeMap {
+        &self.edge_map
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.node_count
+    }
+
+    pub fn root(&self) -> &Bytes {
+        &self.root
+    }
+
+    /// Encodes the key as the node count and edge count, each edge's `(edge, node, node)`
+    /// in `EdgeIndex` order, then the 32 byte root. All integers are u64 little-endian.
+    pub fn to_bytes(&self) -> Bytes {
+        let mut edges: Vec<_> = self.edge_map.iter().collect();
+        edges.sort_unstable_by_key(|(edge, _)| **edge);
+
+        let mut buf = BytesMut::with_capacity(16 + edges.len() * 24 + self.root.len());
+        buf.put_u64_le(self.node_count as u64);
+        buf.put_u64_le(edges.len() as u64);
+        for (edge, (node1, node2)) in edges {
+            buf.put_u64_le(edge.index() as u64);
+            buf.put_u64_le(node1.index() as u64);
+            buf.put_u64_le(node2.index() as u64);
+        }
+        buf.put_slice(&self.root);
+        buf.freeze()
+    }
+
+    /// Decodes a key written by [`VerificationKey::to_bytes`], rejecting it if the
+    /// embedded root doesn't match the decoded edge map.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ZkProofError> {
+        let mut buf = bytes;
+        if buf.remaining() < 16 {
+            return Err(ZkProofError::InvalidVerificationKey);
+        }
+        let node_count = buf.get_u64_le() as usize;
+        let edge_count = buf.get_u64_le() as usize;
+        if buf.remaining() != edge_count.saturating_mul(24).saturating_add(32) {
+            return Err(ZkProofError::InvalidVerificationKey);
+        }
+
+        let mut edge_map = EdgeNodeMap::with_capacity(edge_count);
+        for _ in 0..edge_count {
+            let edge = EdgeIndex::new(buf.get_u64_le() as usize);
+            let node1 = NodeIndex::new(buf.get_u64_le() as usize);
+            let node2 = NodeIndex::new(buf.get_u64_le() as usize);
+            edge_map.insert(edge, (node1, node2));
+        }
+
+        let key = Self::new(edge_map, node_count);
+        if key.root != buf {
+            return Err(ZkProofError::EdgeMapRootMismatch);
+        }
+        Ok(key)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Prover, SudokuGrid, Verifier};
+
+    use super::*;
+
+    #[test]
+    fn test_verification_key_round_trip() {
+        let grid = SudokuGrid::test_solution();
+        let (mut prover, edge_map) = Prover::new(&grid).unwrap();
+        let key = prover.verification_key();
+        assert_eq!(key.edge_map(), &edge_map);
+
+        let bytes = key.to_bytes();
+        let decoded = VerificationKey::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded, key);
+
+        let mut verifier = Verifier::from_verification_key(decoded);
+        assert!(verifier.assert_edge_map_root(key.root()).is_ok());
+        for _ in 0..10 {
+            let challenge = verifier.receive_commitment(prover.start_round()).unwrap();
+            let response = prover.respond_to_challenge(challenge).unwrap();
+            assert!(verifier.verify_response(response).unwrap().success);
+        }
+    }
+
+    #[test]
+    fn test_verification_key_rejects_corruption() {
+        let grid = SudokuGrid::test_solution();
+        let (prover, _) = Prover::new(&grid).unwrap();
+        let bytes = prover.verification_key().to_bytes().to_vec();
+
+        // Rewire the first edge's first node
+        let mut corrupted = bytes.clone();
+        corrupted[24] ^= 1;
+        assert!(matches!(
+            VerificationKey::from_bytes(&corrupted),
+            Err(ZkProofError::EdgeMapRootMismatch)
+        ));
+
+        assert!(matches!(
+            VerificationKey::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(ZkProofError::InvalidVerificationKey)
+        ));
+    }
+}